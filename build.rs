@@ -0,0 +1,17 @@
+use std::process::Command;
+
+// Bake the git commit into the binary for --version; builds from a tarball
+// just go without.
+fn main() {
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok());
+    if let Some(commit) = commit {
+        println!("cargo:rustc-env=POMODORO_GIT_COMMIT={}", commit.trim());
+    }
+}
@@ -0,0 +1,465 @@
+pub const USAGE: &str = "\
+Usage: pomodoro [OPTIONS]
+
+Options:
+      --work <MINUTES>         Length of a work session [default: 25]
+      --short-break <MINUTES>  Length of a short break [default: 5]
+      --long-break <MINUTES>   Length of a long break [default: 30]
+      --deep-work <MINUTES>    Length of a deep work phase, started with 'd'
+                               [default: 90]
+      --pomodoros-per-set <N>  Pomodoros before a long break [default: 4]
+      --plan <STEPS>           Run these phases in order and then start over, in
+                               place of the lengths above: W for work, B for a
+                               short break and LB for a long one, each followed by
+                               minutes, e.g. W50,B10,W50,LB30
+      --tick-ms <MS>           Milliseconds per timer second [default: 1000]
+      --start-paused           Open paused and wait for space before starting
+      --manual                 Wait for Enter before starting each phase
+      --at <HH:MM>             Wait and start the first pomodoro at this local time,
+                               or right away if it has passed; space starts early
+      --stopwatch              Count up from zero instead of down; the skip key
+                               logs the session and starts over
+      --auto-pause-after <SECS>
+                               Pause work after this long without a key press or
+                               mouse event; any input resumes
+      --loop <N>               Stop after N full sets, 0 to run forever [default: 0]
+      --dry-run                Play the schedule 100x faster than --tick-ms, without
+                               writing history
+      --schedule <NAME>        Start with one of the config's [schedules]
+      --task <NAME>            What you're working on
+      --notes                  Ask for a one-line note after each pomodoro and save
+                               it with the session
+      --micro-activities       Now and then, open a break with a 30-second exercise
+      --seed <N>               Seed for picking micro-activities, to repeat a run
+      --work-label <TEXT>      Name shown for work sessions [default: Work]
+      --short-break-label <TEXT>
+                               Name shown for short breaks [default: Break]
+      --long-break-label <TEXT>
+                               Name shown for long breaks [default: Lunch]
+      --no-chart               Show a plain text countdown instead of the chart
+      --set-title              Show the phase and time left in the terminal's title,
+                               updated once a minute
+      --no-mouse               Leave the mouse to the terminal so text can be
+                               selected; chart clicks won't pause
+      --lang <LANG>            Language of the UI: en, ja or de [default: en]
+      --theme <NAME>           Color theme: dark, light, solarized or colorblind
+                               [default: dark]
+      --colorblind             Use the colorblind theme and draw each wave with its
+                               own marker
+      --marker <STYLE>         How the chart draws points: braille, dot, block or bar
+                               [default: braille]
+      --now-color <COLOR>      Color of the chart's \"now\" marker [default: white]
+      --window-secs <N>        Seconds of history across the chart, up to a day
+                               [default: 1800]
+      --sparkline-hours <N>    Hours of history in the sparkline, 0 to hide [default: 12]
+      --goal <N>               Pomodoros to aim for today
+      --show-stats             Show how long you've focused today
+      --clock <FORMAT>         Show the time of day in a status line: 24h or 12h
+      --serve <ADDR>           Answer GET /status with the timer state as JSON,
+                               e.g. --serve 127.0.0.1:7070
+      --log-file <PATH>        Write a debug log of the event loop to PATH
+      --config <PATH>          Config file [default: ~/.config/pomodoro/config.toml]
+      --no-notify              Don't send desktop notifications
+      --no-confirm             Quit on 'q' without asking, even mid-phase
+      --strict                 Lock skip, reset, seeking and schedule switching
+                               while a phase runs, and always confirm quitting
+      --strict-pause           Like --strict, and lock pausing too
+      --bell                   Ring the terminal bell when a phase ends: once after
+                               work, twice after a short break and a long ring
+                               after a long break
+      --sound <PATH>           Play a WAV file when a phase ends, falling back to
+                               the bell (needs the `sound` feature)
+      --webhook <URL>          POST each finished work session to URL as JSON
+                               (needs the `webhook` feature and curl)
+      --mute-after <HH:MM>     Keep quiet from this local time until 06:00
+      --summary                Print today's stats and exit
+      --json                   With --summary, print the stats as a JSON object
+      --export-csv <PATH>      Write the whole history to PATH as CSV and exit
+      --print-once             Print the running instance's phase and time left, or
+                               \"idle\", and exit
+      --state-file <PATH>      Share the running instance's state with --print-once
+                               through PATH; the config's share_state = true uses
+                               ~/.local/state/pomodoro/state.json, which is also
+                               where --print-once looks without this option
+  -h, --help                   Print this help and exit
+  -V, --version                Print the version and exit
+
+Environment:
+  POMODORO_WORK, POMODORO_SHORT_BREAK, POMODORO_LONG_BREAK
+                               Phase lengths in minutes; override the config file
+                               but not the options above
+
+Exit status:
+  0  at least one full set of pomodoros was completed
+  1  invalid arguments, configuration or a runtime error
+  2  quit before finishing a full set
+";
+
+use crate::clock::{ClockFormat, TimeOfDay};
+use crate::theme::{self, Palette};
+use crate::timer::{Phase, Step};
+use ratatui::{style::Color, symbols::Marker};
+use std::{net::SocketAddr, path::PathBuf};
+
+// the chart keeps this many samples per wave in memory
+const MAX_WINDOW_SECS: usize = 86_400;
+
+#[derive(Default)]
+pub struct Args {
+    pub work: Option<u64>,
+    pub short_break: Option<u64>,
+    pub long_break: Option<u64>,
+    pub deep_work: Option<u64>,
+    pub pomodoros_per_set: Option<u32>,
+    pub plan: Option<Vec<Step>>,
+    pub loop_sets: u32,
+    pub tick_ms: Option<u64>,
+    pub dry_run: bool,
+    pub manual: bool,
+    pub at: Option<TimeOfDay>,
+    pub stopwatch: bool,
+    pub auto_pause_after: Option<u64>,
+    pub start_paused: bool,
+    pub now_color: Option<Color>,
+    pub marker: Option<Marker>,
+    pub theme: Option<Palette>,
+    pub colorblind: bool,
+    pub lang: Option<String>,
+    pub sparkline_hours: Option<usize>,
+    pub window_secs: Option<usize>,
+    pub task: Option<String>,
+    pub notes: bool,
+    pub micro_activities: bool,
+    pub seed: Option<u64>,
+    pub schedule: Option<String>,
+    // in `Phase::ALL` order
+    pub labels: [Option<String>; 3],
+    pub log_file: Option<PathBuf>,
+    pub serve: Option<SocketAddr>,
+    pub config: Option<PathBuf>,
+    pub state_file: Option<PathBuf>,
+    pub no_notify: bool,
+    pub no_confirm: bool,
+    pub strict: bool,
+    pub strict_pause: bool,
+    pub bell: bool,
+    pub sound: Option<PathBuf>,
+    pub webhook: Option<String>,
+    pub mute_after: Option<TimeOfDay>,
+    pub no_chart: bool,
+    pub no_mouse: bool,
+    pub set_title: bool,
+    pub show_stats: bool,
+    pub goal: Option<u32>,
+    pub clock: Option<ClockFormat>,
+}
+
+pub enum Command {
+    Run(Box<Args>),
+    Summary { json: bool },
+    ExportCsv(PathBuf),
+    PrintOnce { state_file: Option<PathBuf> },
+    Help,
+    Version,
+}
+
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut parsed = Args::default();
+    let mut summary = false;
+    let mut json = false;
+    let mut export_csv = None;
+    let mut print_once = false;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "--summary" => summary = true,
+            "--json" => json = true,
+            "--print-once" => print_once = true,
+            "--state-file" => parsed.state_file = Some(value(&arg, args.next())?.into()),
+            "--export-csv" => export_csv = Some(PathBuf::from(value(&arg, args.next())?)),
+            "--work" => parsed.work = Some(positive(&arg, args.next())?),
+            "--short-break" => parsed.short_break = Some(positive(&arg, args.next())?),
+            "--long-break" => parsed.long_break = Some(positive(&arg, args.next())?),
+            "--deep-work" => parsed.deep_work = Some(positive(&arg, args.next())?),
+            "--no-notify" => parsed.no_notify = true,
+            "--no-confirm" => parsed.no_confirm = true,
+            "--strict" => parsed.strict = true,
+            "--strict-pause" => {
+                parsed.strict = true;
+                parsed.strict_pause = true;
+            }
+            "--bell" => parsed.bell = true,
+            "--sound" => parsed.sound = Some(value(&arg, args.next())?.into()),
+            "--webhook" => parsed.webhook = Some(url(&arg, args.next())?),
+            "--mute-after" => parsed.mute_after = Some(time_of_day(&arg, args.next())?),
+            "--no-chart" => parsed.no_chart = true,
+            "--no-mouse" => parsed.no_mouse = true,
+            "--set-title" => parsed.set_title = true,
+            "--show-stats" => parsed.show_stats = true,
+            "--goal" => parsed.goal = Some(positive(&arg, args.next())?),
+            "--clock" => {
+                let value = value(&arg, args.next())?;
+                parsed.clock = Some(value.parse().map_err(|_| {
+                    format!(
+                        "invalid format '{}' for {}: expected 24h or 12h",
+                        value, arg
+                    )
+                })?)
+            }
+            "--dry-run" => parsed.dry_run = true,
+            "--manual" => parsed.manual = true,
+            "--at" => parsed.at = Some(time_of_day(&arg, args.next())?),
+            "--stopwatch" => parsed.stopwatch = true,
+            "--auto-pause-after" => parsed.auto_pause_after = Some(positive(&arg, args.next())?),
+            "--start-paused" => parsed.start_paused = true,
+            "--pomodoros-per-set" => parsed.pomodoros_per_set = Some(positive(&arg, args.next())?),
+            "--plan" => parsed.plan = Some(plan(&arg, args.next())?),
+            "--tick-ms" => parsed.tick_ms = Some(positive(&arg, args.next())?),
+            "--marker" => parsed.marker = Some(marker(&arg, args.next())?),
+            "--now-color" => parsed.now_color = Some(color(&arg, args.next())?),
+            "--notes" => parsed.notes = true,
+            "--micro-activities" => parsed.micro_activities = true,
+            "--seed" => parsed.seed = Some(number(&arg, args.next())?),
+            "--task" => parsed.task = Some(value(&arg, args.next())?),
+            "--schedule" => parsed.schedule = Some(value(&arg, args.next())?),
+            "--work-label" => parsed.labels[Phase::Work.index()] = Some(value(&arg, args.next())?),
+            "--short-break-label" => {
+                parsed.labels[Phase::ShortBreak.index()] = Some(value(&arg, args.next())?)
+            }
+            "--long-break-label" => {
+                parsed.labels[Phase::LongBreak.index()] = Some(value(&arg, args.next())?)
+            }
+            "--log-file" => parsed.log_file = Some(value(&arg, args.next())?.into()),
+            "--lang" => parsed.lang = Some(value(&arg, args.next())?),
+            "--theme" => parsed.theme = Some(palette(&arg, args.next())?),
+            "--colorblind" => parsed.colorblind = true,
+            "--window-secs" => {
+                let secs = positive(&arg, args.next())?;
+                if secs > MAX_WINDOW_SECS {
+                    return Err(format!(
+                        "invalid value '{}' for {}: at most {} (a day)",
+                        secs, arg, MAX_WINDOW_SECS
+                    ));
+                }
+                parsed.window_secs = Some(secs);
+            }
+            "--sparkline-hours" => parsed.sparkline_hours = Some(number(&arg, args.next())?),
+            "--loop" => parsed.loop_sets = number(&arg, args.next())?,
+            "--serve" => {
+                let value = value(&arg, args.next())?;
+                parsed.serve = Some(value.parse().map_err(|_| {
+                    format!("invalid address '{}' for {}: expected IP:PORT", value, arg)
+                })?)
+            }
+            "--config" => parsed.config = Some(value(&arg, args.next())?.into()),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+    if parsed.plan.is_some() {
+        let conflicts = [
+            ("--work", parsed.work.is_some()),
+            ("--short-break", parsed.short_break.is_some()),
+            ("--long-break", parsed.long_break.is_some()),
+            ("--pomodoros-per-set", parsed.pomodoros_per_set.is_some()),
+            ("--schedule", parsed.schedule.is_some()),
+            ("--stopwatch", parsed.stopwatch),
+        ];
+        if let Some((flag, _)) = conflicts.iter().find(|(_, given)| *given) {
+            return Err(format!("--plan can't be combined with {}", flag));
+        }
+    }
+    if print_once {
+        return Ok(Command::PrintOnce {
+            state_file: parsed.state_file,
+        });
+    }
+    if let Some(path) = export_csv {
+        if summary {
+            return Err("--export-csv can't be combined with --summary".to_string());
+        }
+        return Ok(Command::ExportCsv(path));
+    }
+    if summary {
+        return Ok(Command::Summary { json });
+    }
+    if json {
+        return Err("--json can only be used with --summary".to_string());
+    }
+    Ok(Command::Run(Box::new(parsed)))
+}
+
+fn value(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("{} requires a value", flag))
+}
+
+fn positive<T>(flag: &str, value: Option<String>) -> Result<T, String>
+where
+    T: std::str::FromStr + Default + PartialOrd,
+{
+    let value = self::value(flag, value)?;
+    match value.parse::<T>() {
+        Ok(n) if n > T::default() => Ok(n),
+        _ => Err(format!(
+            "invalid value '{}' for {}: expected a positive integer",
+            value, flag
+        )),
+    }
+}
+
+fn number<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = self::value(flag, value)?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{}' for {}: expected a number", value, flag))
+}
+
+fn color(flag: &str, value: Option<String>) -> Result<Color, String> {
+    let value = self::value(flag, value)?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid color '{}' for {}", value, flag))
+}
+
+fn marker(flag: &str, value: Option<String>) -> Result<Marker, String> {
+    let value = self::value(flag, value)?;
+    match value.as_str() {
+        "braille" => Ok(Marker::Braille),
+        "dot" => Ok(Marker::Dot),
+        "block" => Ok(Marker::Block),
+        "bar" => Ok(Marker::Bar),
+        _ => Err(format!(
+            "invalid marker '{}' for {}: expected braille, dot, block or bar",
+            value, flag
+        )),
+    }
+}
+
+fn url(flag: &str, value: Option<String>) -> Result<String, String> {
+    let value = self::value(flag, value)?;
+    if value.starts_with("http://") || value.starts_with("https://") {
+        Ok(value)
+    } else {
+        Err(format!(
+            "invalid URL '{}' for {}: expected http:// or https://",
+            value, flag
+        ))
+    }
+}
+
+fn time_of_day(flag: &str, value: Option<String>) -> Result<TimeOfDay, String> {
+    let value = self::value(flag, value)?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid time '{}' for {}: expected HH:MM", value, flag))
+}
+
+// comma-separated steps such as W25, B5 or LB30
+fn plan(flag: &str, value: Option<String>) -> Result<Vec<Step>, String> {
+    let value = self::value(flag, value)?;
+    let mut steps = Vec::new();
+    for token in value.split(',').map(str::trim) {
+        let upper = token.to_ascii_uppercase();
+        let (phase, minutes) = if let Some(minutes) = upper.strip_prefix("LB") {
+            (Phase::LongBreak, minutes)
+        } else if let Some(minutes) = upper.strip_prefix('B') {
+            (Phase::ShortBreak, minutes)
+        } else if let Some(minutes) = upper.strip_prefix('W') {
+            (Phase::Work, minutes)
+        } else {
+            return Err(format!(
+                "invalid step '{}' in {}: expected W, B or LB followed by minutes, e.g. W25",
+                token, flag
+            ));
+        };
+        match minutes.parse::<u64>() {
+            Ok(minutes) if minutes > 0 => steps.push(Step { phase, minutes }),
+            _ => {
+                return Err(format!(
+                    "invalid step '{}' in {}: expected a positive number of minutes",
+                    token, flag
+                ))
+            }
+        }
+    }
+    Ok(steps)
+}
+
+fn palette(flag: &str, value: Option<String>) -> Result<Palette, String> {
+    let value = self::value(flag, value)?;
+    Palette::named(&value).ok_or_else(|| {
+        format!(
+            "unknown theme '{}' for {}: expected one of {}",
+            value,
+            flag,
+            theme::NAMES.join(", ")
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Result<Args, String> {
+        match parse(args.iter().map(|arg| arg.to_string()))? {
+            Command::Run(args) => Ok(*args),
+            _ => panic!("not a run: {:?}", args),
+        }
+    }
+
+    #[test]
+    fn maps_each_marker_name() {
+        for (name, marker) in [
+            ("braille", Marker::Braille),
+            ("dot", Marker::Dot),
+            ("block", Marker::Block),
+            ("bar", Marker::Bar),
+        ] {
+            assert_eq!(args(&["--marker", name]).unwrap().marker, Some(marker));
+        }
+        assert_eq!(args(&[]).unwrap().marker, None);
+    }
+
+    #[test]
+    fn rejects_an_unknown_or_missing_marker() {
+        let Err(err) = args(&["--marker", "star"]) else {
+            panic!("accepted --marker star");
+        };
+        assert!(err.contains("'star'"), "{}", err);
+        assert!(args(&["--marker"]).is_err());
+    }
+
+    #[test]
+    fn parses_a_plan_into_steps() {
+        let plan = args(&["--plan", "W25,B5,W25, lb30"]).unwrap().plan.unwrap();
+        let step = |phase, minutes| Step { phase, minutes };
+        assert_eq!(
+            plan,
+            [
+                step(Phase::Work, 25),
+                step(Phase::ShortBreak, 5),
+                step(Phase::Work, 25),
+                step(Phase::LongBreak, 30),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_malformed_plan_names_the_bad_step() {
+        for (plan, bad) in [
+            ("W25,X5", "'X5'"),
+            ("W25,B", "'B'"),
+            ("W25,B0", "'B0'"),
+            ("W25,,B5", "''"),
+            ("W-5", "'W-5'"),
+        ] {
+            let Err(err) = args(&["--plan", plan]) else {
+                panic!("accepted --plan {}", plan);
+            };
+            assert!(err.contains(bad), "{}: {}", plan, err);
+        }
+        assert!(args(&["--plan"]).is_err());
+    }
+}
@@ -0,0 +1,248 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalTime {
+    pub date: Date,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+// A wall-clock time of day, to the minute, as written on the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TimeOfDay {
+    pub hour: u32,
+    pub minute: u32,
+}
+
+impl TimeOfDay {
+    pub const fn new(hour: u32, minute: u32) -> TimeOfDay {
+        TimeOfDay { hour, minute }
+    }
+
+    // Whether this falls in [from, until), a range that may wrap past midnight.
+    pub fn is_between(self, from: TimeOfDay, until: TimeOfDay) -> bool {
+        if from <= until {
+            from <= self && self < until
+        } else {
+            from <= self || self < until
+        }
+    }
+}
+
+impl std::str::FromStr for TimeOfDay {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<TimeOfDay, ()> {
+        let (hour, minute) = s.split_once(':').ok_or(())?;
+        if hour.is_empty() || hour.len() > 2 || minute.len() != 2 {
+            return Err(());
+        }
+        let hour: u32 = hour.parse().map_err(|_| ())?;
+        let minute: u32 = minute.parse().map_err(|_| ())?;
+        if hour >= 24 || minute >= 60 {
+            return Err(());
+        }
+        Ok(TimeOfDay { hour, minute })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockFormat {
+    H24,
+    H12,
+}
+
+impl std::str::FromStr for ClockFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<ClockFormat, ()> {
+        match s {
+            "24h" => Ok(ClockFormat::H24),
+            "12h" => Ok(ClockFormat::H12),
+            _ => Err(()),
+        }
+    }
+}
+
+impl LocalTime {
+    pub fn time_of_day(&self) -> TimeOfDay {
+        TimeOfDay::new(self.hour, self.minute)
+    }
+
+    pub fn format(&self, format: ClockFormat) -> String {
+        match format {
+            ClockFormat::H24 => format!("{:02}:{:02}:{:02}", self.hour, self.minute, self.second),
+            ClockFormat::H12 => format!(
+                "{:2}:{:02}:{:02} {}",
+                (self.hour + 11) % 12 + 1,
+                self.minute,
+                self.second,
+                if self.hour < 12 { "AM" } else { "PM" }
+            ),
+        }
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// the timestamp of the local midnight that began the day `ts` falls in
+pub fn day_start(ts: u64) -> u64 {
+    let time = local(ts);
+    ts.saturating_sub(u64::from(time.hour * 3600 + time.minute * 60 + time.second))
+}
+
+// Seconds from `now` until the clock next reads `at`, which may be tomorrow;
+// 0 when it reads `at` already.
+pub fn secs_until(now: &LocalTime, at: TimeOfDay) -> u64 {
+    let now = now.hour * 3600 + now.minute * 60 + now.second;
+    let at = at.hour * 3600 + at.minute * 60;
+    u64::from((at + 86_400 - now) % 86_400)
+}
+
+pub fn today() -> Date {
+    local(unix_now()).date
+}
+
+#[cfg(unix)]
+pub fn local(ts: u64) -> LocalTime {
+    let time = ts as libc::time_t;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    // SAFETY: both pointers are valid for the duration of the call and
+    // localtime_r doesn't retain them.
+    if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+        return utc(ts);
+    }
+    LocalTime {
+        date: Date {
+            year: tm.tm_year + 1900,
+            month: (tm.tm_mon + 1) as u32,
+            day: tm.tm_mday as u32,
+        },
+        hour: tm.tm_hour as u32,
+        minute: tm.tm_min as u32,
+        second: tm.tm_sec as u32,
+    }
+}
+
+#[cfg(not(unix))]
+pub fn local(ts: u64) -> LocalTime {
+    utc(ts)
+}
+
+fn utc(ts: u64) -> LocalTime {
+    let days = (ts / 86_400) as i64;
+    let secs = (ts % 86_400) as u32;
+    LocalTime {
+        date: civil_from_days(days),
+        hour: secs / 3600,
+        minute: secs / 60 % 60,
+        second: secs % 60,
+    }
+}
+
+impl Date {
+    pub fn previous(self) -> Date {
+        civil_from_days(days_from_civil(self) - 1)
+    }
+}
+
+// Howard Hinnant's civil-to-days algorithm, the inverse of `civil_from_days`.
+fn days_from_civil(date: Date) -> i64 {
+    let year = i64::from(date.year) - i64::from(date.month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = i64::from(date.month);
+    let doy =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(date.day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+// Howard Hinnant's days-to-civil algorithm.
+fn civil_from_days(days: i64) -> Date {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+    Date { year, month, day }
+}
+
+impl std::fmt::Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quiet_hours_can_wrap_past_midnight() {
+        let from = TimeOfDay::new(22, 0);
+        let until = TimeOfDay::new(6, 0);
+        assert!(!TimeOfDay::new(21, 59).is_between(from, until));
+        assert!(TimeOfDay::new(22, 0).is_between(from, until));
+        assert!(TimeOfDay::new(23, 59).is_between(from, until));
+        assert!(TimeOfDay::new(0, 0).is_between(from, until));
+        assert!(TimeOfDay::new(5, 59).is_between(from, until));
+        assert!(!TimeOfDay::new(6, 0).is_between(from, until));
+    }
+
+    #[test]
+    fn quiet_hours_within_a_day() {
+        let from = TimeOfDay::new(1, 0);
+        let until = TimeOfDay::new(6, 0);
+        assert!(!TimeOfDay::new(0, 30).is_between(from, until));
+        assert!(TimeOfDay::new(3, 0).is_between(from, until));
+        assert!(!TimeOfDay::new(22, 0).is_between(from, until));
+    }
+
+    #[test]
+    fn parses_hours_and_minutes() {
+        assert_eq!("22:00".parse(), Ok(TimeOfDay::new(22, 0)));
+        assert_eq!("7:05".parse(), Ok(TimeOfDay::new(7, 5)));
+        for bad in ["24:00", "12:60", "12:5", "1200", ":30", "ab:cd"] {
+            assert!(bad.parse::<TimeOfDay>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn waits_until_the_time_comes_round_again() {
+        let at = |hour, minute, second| LocalTime {
+            date: Date {
+                year: 2024,
+                month: 3,
+                day: 1,
+            },
+            hour,
+            minute,
+            second,
+        };
+        let two_pm = TimeOfDay::new(14, 0);
+        assert_eq!(secs_until(&at(13, 45, 57), two_pm), 14 * 60 + 3);
+        assert_eq!(secs_until(&at(14, 0, 0), two_pm), 0);
+        // a minute late means tomorrow
+        assert_eq!(secs_until(&at(14, 1, 0), two_pm), 86_400 - 60);
+        assert_eq!(secs_until(&at(23, 59, 59), TimeOfDay::new(0, 0)), 1);
+        assert_eq!(secs_until(&at(0, 0, 1), TimeOfDay::new(0, 0)), 86_399);
+    }
+}
@@ -0,0 +1,362 @@
+use crate::{
+    keys::{self, Action, KeyMap},
+    theme::{self, Palette},
+    timer::Phase,
+    toml::{self, Value},
+};
+use ratatui::style::Color;
+use std::{
+    env,
+    ffi::OsString,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[derive(Default)]
+pub struct Config {
+    pub work_minutes: Option<u64>,
+    pub short_break_minutes: Option<u64>,
+    pub long_break_minutes: Option<u64>,
+    pub pomodoros_per_set: Option<u32>,
+    pub deep_work_minutes: Option<u64>,
+    pub tick_ms: Option<u64>,
+    // terminal size below which the UI asks to be enlarged
+    pub min_width: Option<u16>,
+    pub min_height: Option<u16>,
+    pub notifications: Option<bool>,
+    // write the state file for --print-once at its default path
+    pub share_state: bool,
+    pub now_color: Option<Color>,
+    pub theme: Option<Palette>,
+    // from the [labels] table, in `Phase::ALL` order
+    pub labels: [Option<String>; 3],
+    // notification text for the end of each phase, in `Phase::ALL` order
+    pub end_messages: [Option<String>; 3],
+    // [schedules.NAME] tables, sorted by name
+    pub schedules: Vec<NamedSchedule>,
+    pub keys: KeyMap,
+    pub break_tips: Option<Vec<String>>,
+    pub micro_activities: Option<Vec<String>>,
+    pub lang: Option<String>,
+    // RGB colors the active phase fades between, start to end
+    pub urgency_colors: Option<[(u8, u8, u8); 2]>,
+}
+
+// Unset fields fall back to the top-level settings.
+#[derive(Default)]
+pub struct NamedSchedule {
+    pub name: String,
+    pub work_minutes: Option<u64>,
+    pub short_break_minutes: Option<u64>,
+    pub long_break_minutes: Option<u64>,
+    pub pomodoros_per_set: Option<u32>,
+}
+
+pub fn default_path() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("pomodoro").join("config.toml"))
+}
+
+// A missing file is only an error when the path was given explicitly.
+pub fn load(path: &Path, explicit: bool) -> Result<Config, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound && !explicit => {
+            return Ok(Config::default())
+        }
+        Err(err) => return Err(format!("{}: {}", path.display(), err)),
+    };
+    parse(&text).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+pub fn parse(text: &str) -> Result<Config, String> {
+    let table = toml::parse(text)?;
+    let mut config = Config::default();
+    for (key, value) in &table {
+        match key.as_str() {
+            "work_minutes" => config.work_minutes = Some(positive(key, value)?),
+            "short_break_minutes" => config.short_break_minutes = Some(positive(key, value)?),
+            "long_break_minutes" => config.long_break_minutes = Some(positive(key, value)?),
+            "deep_work_minutes" => config.deep_work_minutes = Some(positive(key, value)?),
+            "pomodoros_per_set" => {
+                config.pomodoros_per_set = Some(
+                    positive(key, value)?
+                        .try_into()
+                        .map_err(|_| format!("'{}' is too large", key))?,
+                )
+            }
+            "tick_ms" => config.tick_ms = Some(positive(key, value)?),
+            "min_width" => config.min_width = Some(cells(key, value)?),
+            "min_height" => config.min_height = Some(cells(key, value)?),
+            "notifications" => config.notifications = Some(boolean(key, value)?),
+            "share_state" => config.share_state = boolean(key, value)?,
+            "now_color" => config.now_color = Some(color(key, value)?),
+            "theme" => {
+                let name = string(key, value)?;
+                config.theme = Some(Palette::named(name).ok_or_else(|| {
+                    format!(
+                        "unknown theme '{}': expected one of {}",
+                        name,
+                        theme::NAMES.join(", ")
+                    )
+                })?)
+            }
+            "labels" => config.labels = labels(value)?,
+            "work_end_msg" => {
+                config.end_messages[Phase::Work.index()] = Some(string(key, value)?.to_string())
+            }
+            "short_break_end_msg" => {
+                config.end_messages[Phase::ShortBreak.index()] =
+                    Some(string(key, value)?.to_string())
+            }
+            "long_break_end_msg" => {
+                config.end_messages[Phase::LongBreak.index()] =
+                    Some(string(key, value)?.to_string())
+            }
+            "schedules" => config.schedules = schedules(value)?,
+            "keys" => config.keys = key_map(value)?,
+            "urgency_colors" => config.urgency_colors = Some(rgb_pair(key, value)?),
+            "lang" => config.lang = Some(string(key, value)?.to_string()),
+            "break_tips" => config.break_tips = Some(strings(key, value)?),
+            "micro_activities" => config.micro_activities = Some(strings(key, value)?),
+            _ => return Err(format!("unknown key '{}'", key)),
+        }
+    }
+    Ok(config)
+}
+
+// POMODORO_* variables for the phase lengths, in minutes. They take over from
+// the config file but not the command line; a set variable that isn't a
+// positive number is left out with a warning.
+pub fn apply_env(config: &mut Config) -> Vec<String> {
+    apply_vars(config, |name| env::var_os(name))
+}
+
+fn apply_vars(config: &mut Config, var: impl Fn(&str) -> Option<OsString>) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for (name, field) in [
+        ("POMODORO_WORK", &mut config.work_minutes),
+        ("POMODORO_SHORT_BREAK", &mut config.short_break_minutes),
+        ("POMODORO_LONG_BREAK", &mut config.long_break_minutes),
+    ] {
+        let Some(value) = var(name) else {
+            continue;
+        };
+        match value.to_str().and_then(|v| v.trim().parse::<u64>().ok()) {
+            Some(minutes) if minutes > 0 => *field = Some(minutes),
+            _ => warnings.push(format!(
+                "ignoring {}={:?}: expected a positive number of minutes",
+                name, value
+            )),
+        }
+    }
+    warnings
+}
+
+fn schedules(value: &Value) -> Result<Vec<NamedSchedule>, String> {
+    let Value::Table(table) = value else {
+        return Err(format!(
+            "'schedules' must be a table, found {}",
+            value.type_name()
+        ));
+    };
+    let mut schedules = Vec::new();
+    for (name, value) in table {
+        let Value::Table(fields) = value else {
+            return Err(format!(
+                "'schedules.{}' must be a table, found {}",
+                name,
+                value.type_name()
+            ));
+        };
+        let mut schedule = NamedSchedule {
+            name: name.clone(),
+            ..NamedSchedule::default()
+        };
+        for (key, value) in fields {
+            match key.as_str() {
+                "work_minutes" => schedule.work_minutes = Some(positive(key, value)?),
+                "short_break_minutes" => schedule.short_break_minutes = Some(positive(key, value)?),
+                "long_break_minutes" => schedule.long_break_minutes = Some(positive(key, value)?),
+                "pomodoros_per_set" => {
+                    schedule.pomodoros_per_set = Some(
+                        positive(key, value)?
+                            .try_into()
+                            .map_err(|_| format!("'{}' is too large", key))?,
+                    )
+                }
+                _ => return Err(format!("unknown key 'schedules.{}.{}'", name, key)),
+            }
+        }
+        schedules.push(schedule);
+    }
+    Ok(schedules)
+}
+
+fn labels(value: &Value) -> Result<[Option<String>; 3], String> {
+    let Value::Table(table) = value else {
+        return Err(format!(
+            "'labels' must be a table, found {}",
+            value.type_name()
+        ));
+    };
+    let mut labels: [Option<String>; 3] = Default::default();
+    for (key, value) in table {
+        let phase = Phase::ALL
+            .into_iter()
+            .find(|phase| phase.key() == key)
+            .ok_or_else(|| format!("unknown key 'labels.{}'", key))?;
+        labels[phase.index()] = Some(string(key, value)?.to_string());
+    }
+    Ok(labels)
+}
+
+fn key_map(value: &Value) -> Result<KeyMap, String> {
+    let Value::Table(table) = value else {
+        return Err(format!(
+            "'keys' must be a table, found {}",
+            value.type_name()
+        ));
+    };
+    let mut bindings = Vec::new();
+    for (key, value) in table {
+        let action = Action::ALL
+            .into_iter()
+            .find(|action| action.key() == key)
+            .ok_or_else(|| format!("unknown key 'keys.{}'", key))?;
+        let name = string(key, value)?;
+        let c = keys::parse(name).ok_or_else(|| {
+            format!(
+                "'keys.{}' must be a single character or \"space\", got '{}'",
+                key, name
+            )
+        })?;
+        bindings.push((action, c));
+    }
+    KeyMap::new(&bindings)
+}
+
+fn positive(key: &str, value: &Value) -> Result<u64, String> {
+    match value {
+        Value::Integer(n) if *n > 0 => Ok(*n as u64),
+        Value::Integer(n) => Err(format!("'{}' must be positive, got {}", key, n)),
+        other => Err(format!(
+            "'{}' must be an integer, found {}",
+            key,
+            other.type_name()
+        )),
+    }
+}
+
+fn cells(key: &str, value: &Value) -> Result<u16, String> {
+    positive(key, value)?
+        .try_into()
+        .map_err(|_| format!("'{}' is too large", key))
+}
+
+fn boolean(key: &str, value: &Value) -> Result<bool, String> {
+    match value {
+        Value::Boolean(b) => Ok(*b),
+        other => Err(format!(
+            "'{}' must be a boolean, found {}",
+            key,
+            other.type_name()
+        )),
+    }
+}
+
+fn string<'a>(key: &str, value: &'a Value) -> Result<&'a str, String> {
+    match value {
+        Value::String(s) => Ok(s),
+        other => Err(format!(
+            "'{}' must be a string, found {}",
+            key,
+            other.type_name()
+        )),
+    }
+}
+
+fn strings(key: &str, value: &Value) -> Result<Vec<String>, String> {
+    let Value::Array(items) = value else {
+        return Err(format!(
+            "'{}' must be an array, found {}",
+            key,
+            value.type_name()
+        ));
+    };
+    items
+        .iter()
+        .map(|item| string(key, item).map(str::to_string))
+        .collect()
+}
+
+fn rgb_pair(key: &str, value: &Value) -> Result<[(u8, u8, u8); 2], String> {
+    let colors = match value {
+        Value::Array(items) if items.len() == 2 => items
+            .iter()
+            .map(|item| match color(key, item)? {
+                Color::Rgb(r, g, b) => Ok((r, g, b)),
+                _ => Err(format!(
+                    "'{}' needs RGB colors like \"#00ff00\", got '{}'",
+                    key,
+                    string(key, item)?
+                )),
+            })
+            .collect::<Result<Vec<_>, String>>()?,
+        _ => return Err(format!("'{}' must be an array of two colors", key)),
+    };
+    Ok([colors[0], colors[1]])
+}
+
+fn color(key: &str, value: &Value) -> Result<Color, String> {
+    let name = string(key, value)?;
+    name.parse()
+        .map_err(|_| format!("'{}' is not a valid color: '{}'", key, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_state_file_is_only_shared_on_request() {
+        assert!(!parse("").unwrap().share_state);
+        assert!(parse("share_state = true").unwrap().share_state);
+        assert!(parse("share_state = 1").is_err());
+    }
+
+    #[test]
+    fn environment_variables_override_the_file_only() {
+        let mut config = parse("work_minutes = 30\nshort_break_minutes = 10").unwrap();
+        let warnings = apply_vars(&mut config, |name| match name {
+            "POMODORO_WORK" => Some("40".into()),
+            "POMODORO_LONG_BREAK" => Some("soon".into()),
+            _ => None,
+        });
+        assert_eq!(config.work_minutes, Some(40));
+        assert_eq!(config.short_break_minutes, Some(10));
+        // the bad value leaves the built-in default in place
+        assert_eq!(config.long_break_minutes, None);
+        assert_eq!(warnings.len(), 1);
+        assert!(
+            warnings[0].contains("POMODORO_LONG_BREAK"),
+            "{}",
+            warnings[0]
+        );
+        // and the command line still wins over both
+        assert_eq!(Some(50).or(config.work_minutes), Some(50));
+    }
+
+    #[test]
+    fn a_zero_minute_variable_is_ignored() {
+        let mut config = Config::default();
+        let warnings = apply_vars(&mut config, |name| {
+            (name == "POMODORO_WORK").then(|| "0".into())
+        });
+        assert_eq!(config.work_minutes, None);
+        assert_eq!(warnings.len(), 1);
+    }
+}
@@ -0,0 +1,754 @@
+use crate::{
+    clock::{self, Date},
+    json::{self, Value},
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env,
+    fs::{self, OpenOptions},
+    io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+// `SessionRecord::phase` for a one-off deep work phase, which counts toward
+// focus time but isn't a pomodoro
+pub const DEEP_WORK: &str = "deep_work";
+
+pub struct SessionRecord {
+    pub phase: String,
+    pub start: u64,
+    pub planned_secs: u64,
+    // time the clock ran, leaving out pauses
+    pub actual_secs: u64,
+    // from start to finish, pauses included
+    pub wall_secs: u64,
+    pub task: Option<String>,
+    pub interruptions: u32,
+    // cut short because the program quit or was killed
+    pub interrupted: bool,
+    // thrown away with reset; logged for its interruptions
+    pub abandoned: bool,
+    // the rest of an interrupted session, picked up on the next launch; the
+    // two together are one pomodoro, counted here
+    pub resumed: bool,
+    // marked with 'u' as not done, so the next work session carried it on
+    pub unfinished: bool,
+    // a line typed in after the session under --notes
+    pub note: Option<String>,
+}
+
+impl SessionRecord {
+    pub fn from_json(value: &Value) -> Option<SessionRecord> {
+        let actual_secs = value.get("actual_secs")?.as_u64()?;
+        Some(SessionRecord {
+            phase: value.get("phase")?.as_str()?.to_string(),
+            start: value.get("start")?.as_u64()?,
+            planned_secs: value.get("planned_secs")?.as_u64()?,
+            actual_secs,
+            // older records predate pause tracking
+            wall_secs: value
+                .get("wall_secs")
+                .and_then(Value::as_u64)
+                .unwrap_or(actual_secs),
+            task: value
+                .get("task")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            // older records predate interruption tracking
+            interruptions: value
+                .get("interruptions")
+                .and_then(Value::as_u64)
+                .map_or(0, |n| n as u32),
+            interrupted: matches!(value.get("interrupted"), Some(Value::Bool(true))),
+            abandoned: matches!(value.get("abandoned"), Some(Value::Bool(true))),
+            resumed: matches!(value.get("resumed"), Some(Value::Bool(true))),
+            unfinished: matches!(value.get("unfinished"), Some(Value::Bool(true))),
+            note: value
+                .get("note")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+        })
+    }
+
+    pub fn is_work(&self) -> bool {
+        self.phase == "work"
+    }
+
+    // a work session that ran to its end or was skipped; one cut short by
+    // quitting or reset only adds to focus time
+    pub fn is_pomodoro(&self) -> bool {
+        self.is_work() && !self.interrupted && !self.abandoned
+    }
+
+    pub fn end(&self) -> u64 {
+        self.start + self.wall_secs
+    }
+
+    pub fn date(&self) -> Date {
+        clock::local(self.start).date
+    }
+
+    // one CSV row, in `CSV_COLUMNS` order
+    pub fn csv_fields(&self) -> [String; 8] {
+        [
+            self.date().to_string(),
+            self.phase.clone(),
+            self.start.to_string(),
+            self.planned_secs.to_string(),
+            self.actual_secs.to_string(),
+            self.wall_secs.to_string(),
+            self.task.clone().unwrap_or_default(),
+            self.interruptions.to_string(),
+        ]
+    }
+
+    pub fn to_json(&self) -> Value {
+        let mut fields = vec![
+            ("phase".to_string(), Value::String(self.phase.clone())),
+            ("start".to_string(), Value::Number(self.start as f64)),
+            (
+                "planned_secs".to_string(),
+                Value::Number(self.planned_secs as f64),
+            ),
+            (
+                "actual_secs".to_string(),
+                Value::Number(self.actual_secs as f64),
+            ),
+            (
+                "wall_secs".to_string(),
+                Value::Number(self.wall_secs as f64),
+            ),
+        ];
+        if let Some(task) = &self.task {
+            fields.push(("task".to_string(), Value::String(task.clone())));
+        }
+        if self.interruptions > 0 {
+            fields.push((
+                "interruptions".to_string(),
+                Value::Number(f64::from(self.interruptions)),
+            ));
+        }
+        if self.interrupted {
+            fields.push(("interrupted".to_string(), Value::Bool(true)));
+        }
+        if self.abandoned {
+            fields.push(("abandoned".to_string(), Value::Bool(true)));
+        }
+        if self.resumed {
+            fields.push(("resumed".to_string(), Value::Bool(true)));
+        }
+        if self.unfinished {
+            fields.push(("unfinished".to_string(), Value::Bool(true)));
+        }
+        if let Some(note) = &self.note {
+            fields.push(("note".to_string(), Value::String(note.clone())));
+        }
+        Value::Object(fields)
+    }
+}
+
+pub fn default_path() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?)
+            .join(".local")
+            .join("share"),
+    };
+    Some(base.join("pomodoro").join("history.jsonl"))
+}
+
+// The record goes out as one write of a whole line and is synced before
+// returning. If an earlier write was cut off mid-line, the new record starts
+// on a line of its own so only the torn one is lost.
+pub fn append(path: &Path, record: &SessionRecord) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(path)?;
+    let mut line = String::new();
+    if file.metadata()?.len() > 0 {
+        let mut last = [0];
+        file.seek(SeekFrom::End(-1))?;
+        file.read_exact(&mut last)?;
+        if last[0] != b'\n' {
+            line.push('\n');
+        }
+    }
+    line.push_str(&record.to_json().to_string());
+    line.push('\n');
+    file.write_all(line.as_bytes())?;
+    file.sync_data()
+}
+
+// Open the log for appending without writing to it, so an unwritable
+// directory shows up before the first session ends.
+pub fn check_writable(path: &Path) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    OpenOptions::new().create(true).append(true).open(path)?;
+    Ok(())
+}
+
+// A missing file is just an empty history; lines that don't parse, like one
+// torn off by a crash, are skipped.
+pub fn load(path: &Path) -> io::Result<Vec<SessionRecord>> {
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut records = Vec::new();
+    // read bytes rather than strings so a write torn inside a multi-byte
+    // character only loses its own line
+    for line in BufReader::new(file).split(b'\n') {
+        let Ok(line) = String::from_utf8(line?) else {
+            continue;
+        };
+        if let Some(record) = json::parse(&line)
+            .ok()
+            .as_ref()
+            .and_then(SessionRecord::from_json)
+        {
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+pub const CSV_COLUMNS: [&str; 8] = [
+    "date",
+    "phase",
+    "start",
+    "planned_secs",
+    "actual_secs",
+    "wall_secs",
+    "task",
+    "interruptions",
+];
+
+// A header line and one line per record; an empty history is just the header.
+pub fn write_csv<W: Write>(mut out: W, records: &[SessionRecord]) -> io::Result<()> {
+    writeln!(out, "{}", CSV_COLUMNS.join(","))?;
+    for record in records {
+        let fields = record.csv_fields().map(|field| csv_quote(&field));
+        writeln!(out, "{}", fields.join(","))?;
+    }
+    out.flush()
+}
+
+// RFC 4180: quote fields with separators, quotes or line breaks, doubling quotes
+fn csv_quote(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Summary {
+    pub pomodoros: u32,
+    pub focus_secs: u64,
+    // the longest run of work sessions that ran their full length
+    pub longest_streak: u32,
+    // every phase, keyed like `SessionRecord::phase`
+    pub by_phase: BTreeMap<String, PhaseTotal>,
+    // sum of `focus_weight` over the work sessions, for `focus_score`
+    pub focus_weight: f64,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct PhaseTotal {
+    pub sessions: u32,
+    pub secs: u64,
+}
+
+// How much one work session counts toward the focus score: the share of its
+// planned length that actually ran, capped at 1, divided by one more than its
+// interruptions. A full session with none counts 1, a skip at halfway with
+// one interruption counts 0.25.
+pub fn focus_weight(actual_secs: u64, planned_secs: u64, interruptions: u32) -> f64 {
+    let ran = if planned_secs == 0 {
+        1.0
+    } else {
+        (actual_secs as f64 / planned_secs as f64).min(1.0)
+    };
+    ran / f64::from(interruptions + 1)
+}
+
+// The mean `focus_weight` of `sessions` work sessions as 0-100; None before the first.
+pub fn focus_score(focus_weight: f64, sessions: u32) -> Option<u32> {
+    (sessions > 0).then(|| (100.0 * focus_weight / f64::from(sessions)).round() as u32)
+}
+
+impl Summary {
+    pub fn to_json(&self, date: Date) -> Value {
+        let number = |n: u64| Value::Number(n as f64);
+        let phases = self
+            .by_phase
+            .iter()
+            .map(|(phase, total)| {
+                let total = Value::Object(vec![
+                    ("sessions".to_string(), number(total.sessions.into())),
+                    ("secs".to_string(), number(total.secs)),
+                ]);
+                (phase.clone(), total)
+            })
+            .collect();
+        Value::Object(vec![
+            ("date".to_string(), Value::String(date.to_string())),
+            ("pomodoros".to_string(), number(self.pomodoros.into())),
+            ("focus_secs".to_string(), number(self.focus_secs)),
+            (
+                "longest_streak".to_string(),
+                number(self.longest_streak.into()),
+            ),
+            (
+                "focus_score".to_string(),
+                focus_score(self.focus_weight, self.pomodoros)
+                    .map_or(Value::Null, |score| number(score.into())),
+            ),
+            // unrounded, unlike the score, so the figures read back exactly
+            ("focus_weight".to_string(), Value::Number(self.focus_weight)),
+            ("phases".to_string(), Value::Object(phases)),
+        ])
+    }
+}
+
+pub fn summarize<'a, I>(records: I) -> Summary
+where
+    I: IntoIterator<Item = &'a SessionRecord>,
+{
+    let mut summary = Summary {
+        pomodoros: 0,
+        focus_secs: 0,
+        longest_streak: 0,
+        by_phase: BTreeMap::new(),
+        focus_weight: 0.0,
+    };
+    let mut streak = 0;
+    for record in records {
+        let total = summary.by_phase.entry(record.phase.clone()).or_default();
+        total.sessions += 1;
+        total.secs += record.actual_secs;
+        if record.phase == DEEP_WORK {
+            summary.focus_secs += record.actual_secs;
+        }
+        if !record.is_work() {
+            continue;
+        }
+        summary.focus_secs += record.actual_secs;
+        if !record.is_pomodoro() {
+            continue;
+        }
+        summary.pomodoros += 1;
+        summary.focus_weight += focus_weight(
+            record.actual_secs,
+            record.planned_secs,
+            record.interruptions,
+        );
+        if record.actual_secs >= record.planned_secs {
+            streak += 1;
+            summary.longest_streak = summary.longest_streak.max(streak);
+        } else {
+            streak = 0;
+        }
+    }
+    summary
+}
+
+// how recently a session must have been cut short to offer picking it back up
+pub const RESUME_WINDOW_SECS: u64 = 5 * 60;
+
+// The last session, if it was cut short by quitting within the resume window
+// and still had time left; the time left is planned less actual.
+pub fn resumable(records: &[SessionRecord], now: u64) -> Option<&SessionRecord> {
+    records.last().filter(|record| {
+        record.interrupted
+            && record.actual_secs < record.planned_secs
+            && now.saturating_sub(record.end()) <= RESUME_WINDOW_SECS
+    })
+}
+
+// Consecutive days with at least one work session, counting back from today.
+// A day without one yet doesn't break the streak until it's over.
+pub fn day_streak(records: &[SessionRecord], today: Date) -> u32 {
+    let days: BTreeSet<Date> = records
+        .iter()
+        .filter(|r| r.is_pomodoro())
+        .map(SessionRecord::date)
+        .collect();
+    let mut day = if days.contains(&today) {
+        today
+    } else {
+        today.previous()
+    };
+    let mut streak = 0;
+    while days.contains(&day) {
+        streak += 1;
+        day = day.previous();
+    }
+    streak
+}
+
+pub fn on_date(records: &[SessionRecord], date: Date) -> impl Iterator<Item = &SessionRecord> {
+    records.iter().filter(move |r| r.date() == date)
+}
+
+// Sessions ending in each of the last `hours` hours, oldest first; the last
+// bucket is the current hour.
+pub fn per_hour(ends: &[u64], now: u64, hours: usize) -> Vec<u64> {
+    let mut buckets = vec![0; hours];
+    let current = now / 3600;
+    for &end in ends {
+        let hour = end / 3600;
+        if hour <= current && current - hour < hours as u64 {
+            buckets[hours - 1 - (current - hour) as usize] += 1;
+        }
+    }
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // noon local time on some fixed day, clear of midnight in any time zone
+    fn noon() -> u64 {
+        clock::day_start(1_700_000_000) + 12 * 3600
+    }
+
+    fn work(start: u64, planned_secs: u64, actual_secs: u64) -> SessionRecord {
+        SessionRecord {
+            phase: "work".to_string(),
+            start,
+            planned_secs,
+            actual_secs,
+            wall_secs: actual_secs,
+            task: None,
+            interruptions: 0,
+            interrupted: false,
+            abandoned: false,
+            resumed: false,
+            unfinished: false,
+            note: None,
+        }
+    }
+
+    fn interrupted(start: u64, planned_secs: u64, actual_secs: u64) -> SessionRecord {
+        SessionRecord {
+            interrupted: true,
+            ..work(start, planned_secs, actual_secs)
+        }
+    }
+
+    // a file of its own under the system temp directory, removed on drop
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> TempFile {
+            let path = env::temp_dir().join(format!(
+                "pomodoro-history-{}-{}.jsonl",
+                std::process::id(),
+                name
+            ));
+            let _ = fs::remove_file(&path);
+            TempFile(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    // the inverse of `Summary::to_json`, less the date
+    fn read_summary(value: &Value) -> Option<Summary> {
+        let number = |value: &Value, key| value.get(key)?.as_u64();
+        let Some(Value::Object(phases)) = value.get("phases") else {
+            return None;
+        };
+        let by_phase = phases
+            .iter()
+            .map(|(phase, total)| {
+                let total = PhaseTotal {
+                    sessions: u32::try_from(number(total, "sessions")?).ok()?,
+                    secs: number(total, "secs")?,
+                };
+                Some((phase.clone(), total))
+            })
+            .collect::<Option<_>>()?;
+        let Some(Value::Number(focus_weight)) = value.get("focus_weight") else {
+            return None;
+        };
+        Some(Summary {
+            pomodoros: u32::try_from(number(value, "pomodoros")?).ok()?,
+            focus_secs: number(value, "focus_secs")?,
+            longest_streak: u32::try_from(number(value, "longest_streak")?).ok()?,
+            by_phase,
+            focus_weight: *focus_weight,
+        })
+    }
+
+    #[test]
+    fn a_truncated_last_line_is_ignored() {
+        let file = TempFile::new("truncated");
+        append(&file.0, &work(noon(), 1500, 1500)).unwrap();
+        let line = work(noon() + 1800, 1500, 1500).to_json().to_string();
+        let mut text = fs::read_to_string(&file.0).unwrap();
+        text.push_str(&line[..line.len() / 2]);
+        fs::write(&file.0, text).unwrap();
+        let records = load(&file.0).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].start, noon());
+        // and the next record still lands on a line of its own
+        append(&file.0, &work(noon() + 3600, 1500, 1500)).unwrap();
+        assert_eq!(load(&file.0).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn a_line_torn_inside_a_character_is_skipped() {
+        let file = TempFile::new("torn-utf8");
+        let task = SessionRecord {
+            task: Some("作業".to_string()),
+            ..work(noon(), 1500, 1500)
+        };
+        let line = task.to_json().to_string();
+        // cut inside the last byte sequence of the first character
+        let cut = line.find('作').unwrap() + 2;
+        let mut bytes = line.as_bytes()[..cut].to_vec();
+        bytes.push(b'\n');
+        fs::write(&file.0, bytes).unwrap();
+        append(&file.0, &work(noon() + 1800, 1500, 1500)).unwrap();
+        let records = load(&file.0).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].start, noon() + 1800);
+    }
+
+    #[test]
+    fn a_record_serializes_to_the_webhook_payload() {
+        let record = SessionRecord {
+            task: Some("write \"report\"".to_string()),
+            interruptions: 1,
+            ..work(1_700_000_000, 1500, 1500)
+        };
+        assert_eq!(
+            record.to_json().to_string(),
+            concat!(
+                r#"{"phase":"work","start":1700000000,"planned_secs":1500,"#,
+                r#""actual_secs":1500,"wall_secs":1500,"task":"write \"report\"","#,
+                r#""interruptions":1}"#
+            )
+        );
+    }
+
+    #[test]
+    fn startup_progress_counts_earlier_pomodoros_from_today() {
+        let today = clock::local(noon()).date;
+        let records = [
+            work(noon() - 86_400, 1500, 1500),
+            work(noon() - 3 * 3600, 1500, 1500),
+            work(noon() - 2 * 3600, 1500, 1500),
+            interrupted(noon() - 3600, 1500, 600),
+            SessionRecord {
+                abandoned: true,
+                ..work(noon(), 1500, 300)
+            },
+        ];
+        let summary = summarize(on_date(&records, today));
+        assert_eq!(summary.pomodoros, 2);
+        assert_eq!(summary.focus_secs, 3900);
+        assert_eq!(day_streak(&records, today), 2);
+    }
+
+    #[test]
+    fn interrupted_sessions_only_add_focus_time() {
+        let records = [
+            work(noon(), 1500, 1500),
+            interrupted(noon() + 2000, 1500, 30),
+            interrupted(noon() + 3000, 1500, 10),
+        ];
+        let summary = summarize(&records);
+        assert_eq!(summary.pomodoros, 1);
+        assert_eq!(summary.focus_secs, 1540);
+        assert_eq!(summary.longest_streak, 1);
+        assert_eq!(
+            focus_score(summary.focus_weight, summary.pomodoros),
+            Some(100)
+        );
+    }
+
+    #[test]
+    fn abandoned_sessions_keep_their_interruptions_but_dont_count() {
+        let record = SessionRecord {
+            abandoned: true,
+            interruptions: 2,
+            ..work(noon(), 1500, 300)
+        };
+        let parsed = SessionRecord::from_json(&record.to_json()).unwrap();
+        assert!(parsed.abandoned);
+        assert_eq!(parsed.interruptions, 2);
+        let summary = summarize([&parsed]);
+        assert_eq!(summary.pomodoros, 0);
+        assert_eq!(summary.focus_secs, 300);
+    }
+
+    #[test]
+    fn a_resumed_session_counts_once() {
+        let records = [
+            interrupted(noon(), 1500, 600),
+            SessionRecord {
+                resumed: true,
+                ..work(noon() + 700, 900, 900)
+            },
+        ];
+        let summary = summarize(&records);
+        assert_eq!(summary.pomodoros, 1);
+        assert_eq!(summary.focus_secs, 1500);
+    }
+
+    #[test]
+    fn interrupted_sessions_dont_make_a_streak_day() {
+        let today = clock::local(noon()).date;
+        let records = [
+            work(noon() - 86_400, 1500, 1500),
+            interrupted(noon(), 1500, 30),
+        ];
+        // today has nothing yet, so the streak stands on yesterday alone
+        assert_eq!(day_streak(&records, today), 1);
+        assert_eq!(day_streak(&records[1..], today), 0);
+    }
+
+    #[test]
+    fn the_summary_json_reads_back_to_the_same_figures() {
+        let records = [
+            work(noon(), 1500, 1500),
+            SessionRecord {
+                phase: "short_break".to_string(),
+                ..work(noon() + 1500, 300, 300)
+            },
+            work(noon() + 1800, 1500, 750),
+        ];
+        let summary = summarize(&records);
+        let date = clock::local(noon()).date;
+        let value = json::parse(&summary.to_json(date).to_string()).unwrap();
+        let number = |key| value.get(key).and_then(Value::as_u64);
+        assert_eq!(
+            value.get("date").and_then(Value::as_str),
+            Some(&*date.to_string())
+        );
+        assert_eq!(number("pomodoros"), Some(2));
+        assert_eq!(number("focus_secs"), Some(2250));
+        assert_eq!(number("longest_streak"), Some(1));
+        assert_eq!(number("focus_score"), Some(75));
+        let phases = value.get("phases").unwrap();
+        for (phase, total) in &summary.by_phase {
+            let read = phases.get(phase).unwrap();
+            assert_eq!(
+                read.get("sessions").and_then(Value::as_u64),
+                Some(total.sessions.into())
+            );
+            assert_eq!(read.get("secs").and_then(Value::as_u64), Some(total.secs));
+        }
+        assert_eq!(summary.by_phase.len(), 2);
+        assert_eq!(read_summary(&value), Some(summary));
+    }
+
+    #[test]
+    fn the_day_streak_counts_days_not_sessions_and_stops_at_a_gap() {
+        let today = clock::local(noon()).date;
+        let day = 86_400;
+        let records = [
+            work(noon() - 4 * day, 1500, 1500),
+            work(noon() - 2 * day, 1500, 1500),
+            work(noon() - day, 1500, 1500),
+            work(noon() - day + 3600, 1500, 1500),
+            work(noon(), 1500, 1500),
+            work(noon() + 3600, 1500, 1500),
+        ];
+        assert_eq!(day_streak(&records, today), 3);
+        // a gap of a day ends it
+        assert_eq!(day_streak(&records[..2], today), 0);
+        assert_eq!(day_streak(&[], today), 0);
+    }
+
+    #[test]
+    fn the_day_streak_splits_days_at_local_midnight() {
+        let midnight = clock::day_start(noon());
+        let today = clock::local(noon()).date;
+        let records = [
+            work(midnight - 60, 1500, 1500),
+            work(midnight + 60, 1500, 1500),
+        ];
+        assert_eq!(records[0].date(), today.previous());
+        assert_eq!(records[1].date(), today);
+        assert_eq!(day_streak(&records, today), 2);
+        assert_eq!(day_streak(&records[..1], today), 1);
+    }
+
+    #[test]
+    fn an_empty_csv_export_is_just_the_header() {
+        let mut out = Vec::new();
+        write_csv(&mut out, &[]).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "date,phase,start,planned_secs,actual_secs,wall_secs,task,interruptions\n"
+        );
+    }
+
+    #[test]
+    fn csv_fields_with_commas_and_quotes_are_quoted() {
+        let records = [
+            SessionRecord {
+                task: Some("write \"docs\", then review".to_string()),
+                interruptions: 1,
+                ..work(noon(), 1500, 1500)
+            },
+            work(noon() + 1800, 1500, 900),
+        ];
+        let mut out = Vec::new();
+        write_csv(&mut out, &records).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        let date = clock::local(noon()).date;
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[1],
+            format!(
+                "{},work,{},1500,1500,1500,\"write \"\"docs\"\", then review\",1",
+                date,
+                noon()
+            )
+        );
+        assert_eq!(
+            lines[2],
+            format!("{},work,{},1500,900,900,,0", date, noon() + 1800)
+        );
+        assert_eq!(csv_quote("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn a_session_quit_within_the_window_is_resumable() {
+        let records = [interrupted(noon(), 1500, 600)];
+        let end = records[0].end();
+        assert!(resumable(&records, end).is_some());
+        assert!(resumable(&records, end + RESUME_WINDOW_SECS).is_some());
+        assert!(resumable(&records, end + RESUME_WINDOW_SECS + 1).is_none());
+    }
+
+    #[test]
+    fn only_an_unfinished_interrupted_last_session_is_resumable() {
+        let end = noon() + 600;
+        assert!(resumable(&[work(noon(), 1500, 600)], end).is_none());
+        assert!(resumable(&[interrupted(noon(), 600, 600)], end).is_none());
+        let records = [interrupted(noon(), 1500, 600), work(end, 300, 0)];
+        assert!(resumable(&records, end).is_none());
+        assert!(resumable(&[], end).is_none());
+    }
+}
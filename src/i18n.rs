@@ -0,0 +1,347 @@
+// The fixed words of the UI in each language --lang knows. Templates take
+// their arguments in place of each "{}", in order.
+
+pub const NAMES: &[&str] = &["en", "ja", "de"];
+
+pub struct Strings {
+    // default phase names, in `Phase::ALL` order
+    pub phases: [&'static str; 3],
+    pub remaining: &'static str,
+    pub elapsed: &'static str,
+    pub stop_and_log: &'static str,
+    pub left: &'static str,
+    pub paused: &'static str,
+    pub auto_paused: &'static str,
+    pub locked: &'static str,
+    pub next: &'static str,
+    pub session: &'static str,
+    pub today: &'static str,
+    pub focused: &'static str,
+    pub score: &'static str,
+    pub all_done: &'static str,
+    pub press_to_begin: &'static str,
+    pub press_enter_to_start: &'static str,
+    pub starting_in: &'static str,
+    pub until_long_break: &'static str,
+    pub deep_work: &'static str,
+    pub long_break_next: &'static str,
+    // shown in place of a task name
+    pub pomodoro: &'static str,
+    pub task: &'static str,
+    pub set: &'static str,
+    pub day_streak: &'static str,
+    pub focus: &'static str,
+    pub from_now_on: &'static str,
+    pub marked_unfinished: &'static str,
+    pub unmarked_unfinished: &'static str,
+    pub history_disabled: &'static str,
+    pub history_unreadable: &'static str,
+    pub history_title: &'static str,
+    // the history view's columns: start, phase, length and task
+    pub history_columns: [&'static str; 4],
+    pub sparkline_title: &'static str,
+    pub no_pomodoros_yet: &'static str,
+    pub help_title: &'static str,
+    // one line per key, in `HELP_KEYS` order
+    pub help: [&'static str; 18],
+    pub quit_prompt: &'static str,
+    pub resume_prompt: &'static str,
+    pub note_prompt: &'static str,
+    pub enlarge: &'static str,
+    // notifications at the end of a phase
+    pub work_done: &'static str,
+    pub break_over: &'static str,
+    pub deep_work_done: &'static str,
+    pub next_up: &'static str,
+    pub goal_reached: &'static str,
+    pub goal_done: &'static str,
+}
+
+const EN: Strings = Strings {
+    phases: ["Break", "Work", "Lunch"],
+    remaining: "remaining",
+    elapsed: "elapsed",
+    stop_and_log: "Press {} to stop and log",
+    left: "left",
+    paused: "PAUSED",
+    auto_paused: "auto-paused (idle)",
+    locked: "Locked",
+    next: "Next",
+    session: "Session",
+    today: "Today",
+    focused: "focused",
+    score: "score",
+    all_done: "All done! 🎉 Press {} to exit",
+    press_to_begin: "Press {} to begin",
+    press_enter_to_start: "Press Enter to start {}",
+    starting_in: "Starting in {}",
+    until_long_break: "{} pomodoros until long break",
+    deep_work: "Deep Work",
+    long_break_next: "Long break next!",
+    pomodoro: "Pomodoro",
+    task: "Task",
+    set: "Set",
+    day_streak: "{}-day streak",
+    focus: "Focus",
+    from_now_on: "{}m from now on",
+    marked_unfinished: "unfinished: the next pomodoro carries on with it",
+    unmarked_unfinished: "no longer marked unfinished",
+    history_disabled: "history disabled: {}",
+    history_unreadable: "failed to read history: {}",
+    history_title: "History: {} latest (j/k to scroll, Esc to close)",
+    history_columns: ["Started", "Phase", "Length", "Task"],
+    sparkline_title: "Pomodoros, last {}h",
+    no_pomodoros_yet: "no pomodoros yet",
+    help_title: "Help",
+    help: [
+        "pause / resume",
+        "skip to the next phase",
+        "start the next phase (--manual)",
+        "rewind / fast-forward a minute",
+        "reset the session",
+        "add / take a minute from this phase",
+        "lengthen / shorten phases like this one (+/- have no capitals)",
+        "switch to the next schedule",
+        "deep work: one long work phase, then back",
+        "edit the task name",
+        "count an interruption",
+        "mark this pomodoro unfinished, to carry on next time",
+        "show / hide the Break, Work, Lunch waves",
+        "show / hide today's cumulative focus",
+        "zen mode: just the countdown, in big digits",
+        "browse past sessions (j/k, Esc)",
+        "toggle this help",
+        "quit",
+    ],
+    quit_prompt: "Quit? (y/n)",
+    resume_prompt: "Resume previous {} session ({} left)? (y/n)",
+    note_prompt: "Note for that pomodoro (Enter to save, Esc to skip)",
+    enlarge: "Please enlarge your terminal to at least {}",
+    work_done: "Work done — take a break!",
+    break_over: "Break over — back to work!",
+    deep_work_done: "Deep work done — back to the schedule!",
+    next_up: "Next up: {} ({} min)",
+    goal_reached: "Daily goal reached! 🎉",
+    goal_done: "{} pomodoros done today",
+};
+
+const JA: Strings = Strings {
+    phases: ["休憩", "作業", "長い休憩"],
+    remaining: "残り",
+    elapsed: "経過",
+    stop_and_log: "{} で停止して記録",
+    left: "残り",
+    paused: "一時停止中",
+    auto_paused: "自動停止中 (離席)",
+    locked: "ロック中",
+    next: "次",
+    session: "セッション",
+    today: "今日",
+    focused: "集中",
+    score: "スコア",
+    all_done: "完了! 🎉 {} で終了",
+    press_to_begin: "{} で開始",
+    press_enter_to_start: "Enter で{}を開始",
+    starting_in: "開始まで {}",
+    until_long_break: "長い休憩まであと {} ポモドーロ",
+    deep_work: "ディープワーク",
+    long_break_next: "次は長い休憩!",
+    pomodoro: "ポモドーロ",
+    task: "タスク",
+    set: "セット",
+    day_streak: "{}日連続",
+    focus: "集中",
+    from_now_on: "これから{}分",
+    marked_unfinished: "未完了: 次のポモドーロで続きをします",
+    unmarked_unfinished: "未完了の印を外しました",
+    history_disabled: "履歴を無効にしました: {}",
+    history_unreadable: "履歴を読めません: {}",
+    history_title: "履歴: 最新 {} 件 (j/k でスクロール、Esc で閉じる)",
+    history_columns: ["開始", "フェーズ", "長さ", "タスク"],
+    sparkline_title: "ポモドーロ (過去 {} 時間)",
+    no_pomodoros_yet: "まだポモドーロはありません",
+    help_title: "ヘルプ",
+    help: [
+        "一時停止 / 再開",
+        "次のフェーズへ進む",
+        "次のフェーズを開始 (--manual)",
+        "1 分戻す / 進める",
+        "セッションをリセット",
+        "このフェーズを 1 分延ばす / 縮める",
+        "同じ種類のフェーズを延ばす / 縮める (+/- に大文字はない)",
+        "次のスケジュールに切り替え",
+        "ディープワーク: 長い作業を 1 回してから戻る",
+        "タスク名を編集",
+        "中断を数える",
+        "このポモドーロを未完了にして次回に続ける",
+        "休憩・作業・長い休憩の波を表示 / 非表示",
+        "今日の累計集中時間を表示 / 非表示",
+        "禅モード: 大きな数字のカウントダウンだけ",
+        "過去のセッションを見る (j/k、Esc)",
+        "このヘルプを表示 / 非表示",
+        "終了",
+    ],
+    quit_prompt: "終了しますか? (y/n)",
+    resume_prompt: "前回の{}を再開しますか (残り {})? (y/n)",
+    note_prompt: "このポモドーロのメモ (Enter で保存、Esc で省略)",
+    enlarge: "端末を {} 以上に広げてください",
+    work_done: "作業終了 — 休憩しましょう!",
+    break_over: "休憩終了 — 作業に戻りましょう!",
+    deep_work_done: "ディープワーク終了 — スケジュールに戻ります!",
+    next_up: "次: {} ({} 分)",
+    goal_reached: "今日の目標を達成! 🎉",
+    goal_done: "今日 {} ポモドーロ完了",
+};
+
+const DE: Strings = Strings {
+    phases: ["Pause", "Arbeit", "Mittag"],
+    remaining: "übrig",
+    elapsed: "vergangen",
+    stop_and_log: "{} stoppt und speichert",
+    left: "übrig",
+    paused: "PAUSIERT",
+    auto_paused: "automatisch pausiert (inaktiv)",
+    locked: "Gesperrt",
+    next: "Als Nächstes",
+    session: "Sitzung",
+    today: "Heute",
+    focused: "fokussiert",
+    score: "Wert",
+    all_done: "Alles erledigt! 🎉 {} zum Beenden",
+    press_to_begin: "{} zum Starten",
+    press_enter_to_start: "Enter startet {}",
+    starting_in: "Start in {}",
+    until_long_break: "Noch {} Pomodoros bis zur langen Pause",
+    deep_work: "Deep Work",
+    long_break_next: "Als Nächstes die lange Pause!",
+    pomodoro: "Pomodoro",
+    task: "Aufgabe",
+    set: "Satz",
+    day_streak: "{} Tage in Folge",
+    focus: "Fokus",
+    from_now_on: "ab jetzt {} min",
+    marked_unfinished: "unfertig: der nächste Pomodoro macht weiter",
+    unmarked_unfinished: "nicht mehr als unfertig markiert",
+    history_disabled: "Verlauf deaktiviert: {}",
+    history_unreadable: "Verlauf nicht lesbar: {}",
+    history_title: "Verlauf: die letzten {} (j/k zum Blättern, Esc zum Schließen)",
+    history_columns: ["Beginn", "Phase", "Dauer", "Aufgabe"],
+    sparkline_title: "Pomodoros, letzte {} h",
+    no_pomodoros_yet: "noch keine Pomodoros",
+    help_title: "Hilfe",
+    help: [
+        "Pause / weiter",
+        "zur nächsten Phase springen",
+        "nächste Phase starten (--manual)",
+        "eine Minute zurück / vor",
+        "Sitzung zurücksetzen",
+        "dieser Phase eine Minute geben / nehmen",
+        "Phasen wie diese verlängern / verkürzen (+/- ohne Großbuchstaben)",
+        "zum nächsten Zeitplan wechseln",
+        "Deep Work: eine lange Arbeitsphase, dann zurück",
+        "Aufgabe bearbeiten",
+        "eine Unterbrechung zählen",
+        "diesen Pomodoro als unfertig markieren, um weiterzumachen",
+        "Wellen für Pause, Arbeit, Mittag ein- / ausblenden",
+        "heutigen Fokus insgesamt ein- / ausblenden",
+        "Zen-Modus: nur der Countdown in großen Ziffern",
+        "frühere Sitzungen durchsehen (j/k, Esc)",
+        "diese Hilfe ein- / ausblenden",
+        "beenden",
+    ],
+    quit_prompt: "Beenden? (y/n)",
+    resume_prompt: "Vorherige Sitzung ({}) fortsetzen ({} übrig)? (y/n)",
+    note_prompt: "Notiz zu diesem Pomodoro (Enter speichert, Esc überspringt)",
+    enlarge: "Bitte vergrößere das Terminal auf mindestens {}",
+    work_done: "Arbeit erledigt — Zeit für eine Pause!",
+    break_over: "Pause vorbei — zurück an die Arbeit!",
+    deep_work_done: "Deep Work erledigt — zurück zum Zeitplan!",
+    next_up: "Als Nächstes: {} ({} min)",
+    goal_reached: "Tagesziel erreicht! 🎉",
+    goal_done: "{} Pomodoros heute erledigt",
+};
+
+// None for a language we don't have; callers fall back to English
+pub fn strings(lang: &str) -> Option<&'static Strings> {
+    match lang {
+        "en" => Some(&EN),
+        "ja" => Some(&JA),
+        "de" => Some(&DE),
+        _ => None,
+    }
+}
+
+pub fn english() -> &'static Strings {
+    &EN
+}
+
+impl Strings {
+    pub fn fill(template: &str, arg: &str) -> String {
+        Strings::fill_all(template, &[arg])
+    }
+
+    // each "{}" in turn takes the next of `args`; extra ones are left as written
+    pub fn fill_all(template: &str, args: &[&str]) -> String {
+        let mut out = String::new();
+        let mut rest = template;
+        for arg in args {
+            let Some((before, after)) = rest.split_once("{}") else {
+                break;
+            };
+            out.push_str(before);
+            out.push_str(arg);
+            rest = after;
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_language_asked_for() {
+        assert_eq!(strings("ja").unwrap().paused, "一時停止中");
+        assert_eq!(strings("de").unwrap().quit_prompt, "Beenden? (y/n)");
+        assert_eq!(strings("en").unwrap().remaining, "remaining");
+        assert!(strings("xx").is_none());
+    }
+
+    #[test]
+    fn templates_take_their_arguments_in_order() {
+        let en = english();
+        assert_eq!(
+            Strings::fill_all(en.resume_prompt, &["Work", "08:12"]),
+            "Resume previous Work session (08:12 left)? (y/n)"
+        );
+        assert_eq!(
+            Strings::fill_all(strings("ja").unwrap().next_up, &["休憩", "5"]),
+            "次: 休憩 (5 分)"
+        );
+        assert_eq!(Strings::fill(en.starting_in, "14:03"), "Starting in 14:03");
+        // an argument that looks like a placeholder isn't filled in again
+        assert_eq!(Strings::fill_all("{} and {}", &["{}", "b"]), "{} and b");
+    }
+
+    #[test]
+    fn every_language_has_the_same_placeholders() {
+        let count = |s: &Strings| {
+            [
+                s.stop_and_log,
+                s.all_done,
+                s.until_long_break,
+                s.day_streak,
+                s.resume_prompt,
+                s.next_up,
+                s.enlarge,
+                s.sparkline_title,
+            ]
+            .map(|template| template.matches("{}").count())
+        };
+        for name in NAMES {
+            assert_eq!(count(strings(name).unwrap()), count(english()), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use crossterm::event::KeyCode;
+
+// The keys that can be rebound from the config's [keys] table; everything
+// else in `run_app` stays where it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Pause,
+    Reset,
+    Skip,
+    Help,
+}
+
+impl Action {
+    pub const ALL: [Action; 5] = [
+        Action::Quit,
+        Action::Pause,
+        Action::Reset,
+        Action::Skip,
+        Action::Help,
+    ];
+
+    // name in the [keys] table
+    pub fn key(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Pause => "pause",
+            Action::Reset => "reset",
+            Action::Skip => "skip",
+            Action::Help => "help",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+// keys with a fixed meaning, which an action can't take over
+const RESERVED: &[char] = &[
+    'e', 'n', 'i', 'u', 'd', 'f', 'z', 'h', '1', '2', '3', '+', '-', '[', ']',
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyMap {
+    // in `Action::ALL` order
+    keys: [char; 5],
+}
+
+impl Default for KeyMap {
+    fn default() -> KeyMap {
+        KeyMap {
+            keys: ['q', ' ', 'r', 's', '?'],
+        }
+    }
+}
+
+impl KeyMap {
+    // Build a map from the [keys] table's entries, keeping the defaults for
+    // actions it leaves out.
+    pub fn new(bindings: &[(Action, char)]) -> Result<KeyMap, String> {
+        let mut map = KeyMap::default();
+        for &(action, c) in bindings {
+            if RESERVED.contains(&c) {
+                return Err(format!(
+                    "'keys.{}': '{}' is already used for something else",
+                    action.key(),
+                    c
+                ));
+            }
+            map.keys[action.index()] = c;
+        }
+        for (i, &c) in map.keys.iter().enumerate() {
+            if let Some(j) = map.keys[..i].iter().position(|&other| other == c) {
+                return Err(format!(
+                    "'keys.{}' and 'keys.{}' are both bound to '{}'",
+                    Action::ALL[j].key(),
+                    Action::ALL[i].key(),
+                    name(c)
+                ));
+            }
+        }
+        Ok(map)
+    }
+
+    pub fn action(&self, code: KeyCode) -> Option<Action> {
+        let KeyCode::Char(c) = code else {
+            return None;
+        };
+        let i = self.keys.iter().position(|&key| key == c)?;
+        Some(Action::ALL[i])
+    }
+
+    pub fn char(&self, action: Action) -> char {
+        self.keys[action.index()]
+    }
+
+    // how the key is written in the help popup
+    pub fn name(&self, action: Action) -> String {
+        name(self.keys[action.index()])
+    }
+}
+
+// "space" or a single character, as written in the config
+pub fn parse(s: &str) -> Option<char> {
+    if s == "space" {
+        return Some(' ');
+    }
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if !c.is_control() => Some(c),
+        _ => None,
+    }
+}
+
+fn name(c: char) -> String {
+    match c {
+        ' ' => "space".to_string(),
+        c => c.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_remapped_quit_key_replaces_the_default() {
+        let map = KeyMap::new(&[(Action::Quit, 'x')]).unwrap();
+        assert_eq!(map.action(KeyCode::Char('x')), Some(Action::Quit));
+        assert_eq!(map.action(KeyCode::Char('q')), None);
+        assert_eq!(map.action(KeyCode::Char(' ')), Some(Action::Pause));
+        assert_eq!(map.action(KeyCode::Esc), None);
+        assert_eq!(map.name(Action::Pause), "space");
+    }
+
+    #[test]
+    fn two_actions_on_one_key_are_an_error() {
+        let err = KeyMap::new(&[(Action::Skip, 'q')]).unwrap_err();
+        assert!(err.contains("'keys.quit' and 'keys.skip'"), "{}", err);
+        let err = KeyMap::new(&[(Action::Quit, 'x'), (Action::Help, 'x')]).unwrap_err();
+        assert!(err.contains("'x'"), "{}", err);
+    }
+
+    #[test]
+    fn reserved_keys_cant_be_bound() {
+        let err = KeyMap::new(&[(Action::Pause, 'e')]).unwrap_err();
+        assert!(err.contains("'keys.pause'"), "{}", err);
+    }
+
+    #[test]
+    fn parses_space_and_single_characters() {
+        assert_eq!(parse("space"), Some(' '));
+        assert_eq!(parse("x"), Some('x'));
+        assert_eq!(parse("xy"), None);
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("\t"), None);
+    }
+}
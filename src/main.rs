@@ -1,28 +1,143 @@
+mod cli;
+mod clock;
+mod config;
+mod history;
+mod i18n;
+mod json;
+mod keys;
+mod logger;
+mod notify;
+mod rng;
+mod serve;
+#[cfg(feature = "sound")]
+mod sound;
+mod state;
+mod theme;
+mod timer;
+mod toml;
+#[cfg(feature = "webhook")]
+mod webhook;
+
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    cursor::Show,
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+        KeyModifiers, MouseButton, MouseEventKind,
+    },
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    style::Print,
+    terminal::{
+        disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
+    },
 };
+use i18n::Strings;
+use keys::{Action, KeyMap};
 use ratatui::{
     backend::{Backend, CrosstermBackend},
-    layout::{Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     symbols,
-    text::Span,
-    widgets::{Axis, Block, Borders, Chart, Dataset},
+    text::{Line, Span},
+    widgets::{
+        block::{Position, Title},
+        Axis, Block, Borders, Chart, Clear, Dataset, Gauge, GraphType, Paragraph, Row, Sparkline,
+        Table, TableState, Wrap,
+    },
     Frame, Terminal,
 };
 use std::{
     error::Error,
-    io,
-    time::{Duration, Instant},
+    fs,
+    io::{self, IsTerminal},
+    panic,
+    path::{Path, PathBuf},
+    process,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant, SystemTime},
 };
+use timer::{Phase, Schedule, Timer, Transition};
+
+// xterm's title stack: save the title on the way in, put it back on the way out
+const PUSH_TITLE: &str = "\x1b[22;0t";
+const POP_TITLE: &str = "\x1b[23;0t";
 
-const WINDOW_SIZE: usize = 1800;
+// samples (ticks) of history across the chart, unless --window-secs says otherwise
+const DEFAULT_WINDOW_SECS: usize = 1800;
+// unless the config says otherwise, below this the gauge and chart can't be
+// laid out meaningfully
+const MIN_WIDTH: u16 = 20;
+const MIN_HEIGHT: u16 = 8;
+// narrower than this the chart is a smear, so fall back to the text view
+const CHART_MIN_WIDTH: u16 = 40;
+// used when every wave is hidden; otherwise the bounds follow the waves
+const Y_BOUNDS: [f64; 2] = [-20.0, 20.0];
+// headroom above the tallest visible wave
+const Y_MARGIN: f64 = 2.0;
+// exit codes, also listed in the usage text
+const EXIT_SET_COMPLETED: i32 = 0;
+const EXIT_ERROR: i32 = 1;
+const EXIT_QUIT_EARLY: i32 = 2;
+
+const DEFAULT_WORK_MINUTES: u64 = 25;
+const DEFAULT_SHORT_BREAK_MINUTES: u64 = 5;
+const DEFAULT_LONG_BREAK_MINUTES: u64 = 30;
+const DEFAULT_DEEP_WORK_MINUTES: u64 = 90;
+const DEFAULT_POMODOROS_PER_SET: u32 = 4;
+const DEFAULT_TICK_MS: u64 = 1000;
+const DEFAULT_SPARKLINE_HOURS: usize = 12;
+// shown one per break unless the config has its own `break_tips`
+const BREAK_TIPS: &[&str] = &[
+    "Look at something 20 feet away for 20 seconds",
+    "Stretch your wrists and fingers",
+    "Stand up and roll your shoulders",
+    "Drink a glass of water",
+    "Close your eyes and take five slow breaths",
+    "Walk around for a minute",
+];
+// --micro-activities picks from these unless the config has its own
+const MICRO_ACTIVITIES: &[&str] = &[
+    "Do 10 pushups",
+    "Do 15 squats",
+    "Hold a plank",
+    "Touch your toes",
+    "March on the spot",
+];
+const MICRO_ACTIVITY_SECS: u64 = 30;
+// a break gets a micro-activity one time in this many
+const MICRO_ACTIVITY_ODDS: usize = 2;
+// ignore toggles closer together than this so a held key doesn't flicker
+const TOGGLE_DEBOUNCE: Duration = Duration::from_millis(300);
+// redraw faster than the timer ticks so the UI stays responsive
+const RENDER_INTERVAL: Duration = Duration::from_millis(100);
+const SEEK_SECS: i64 = 60;
+// a gap this big between the wall clock and Instant means we were suspended
+const SUSPEND_THRESHOLD: Duration = Duration::from_secs(5);
+// --mute-after keeps quiet until this time the next morning
+const QUIET_HOURS_END: clock::TimeOfDay = clock::TimeOfDay::new(6, 0);
+// how long a toast such as "locked" stays in the status line
+const TOAST_DURATION: Duration = Duration::from_secs(2);
+// and a warning such as history being switched off
+const WARNING_DURATION: Duration = Duration::from_secs(10);
+// Break, Work and Lunch waves under --colorblind
+const COLORBLIND_MARKERS: [symbols::Marker; 3] = [
+    symbols::Marker::Dot,
+    symbols::Marker::Braille,
+    symbols::Marker::Bar,
+];
+// --dry-run plays the schedule this many times faster than --tick-ms
+const DRY_RUN_SPEEDUP: u32 = 100;
+// the 'h' view only reads this many of the latest sessions
+const HISTORY_VIEW_LIMIT: usize = 200;
 
 #[derive(Clone)]
 pub struct SinSignal {
     x: f64,
+    // how far `x` has been shifted back; keeps the wave continuous
+    offset: f64,
     interval: f64,
     period: f64,
     scale: f64,
@@ -32,11 +147,19 @@ impl SinSignal {
     pub fn new(interval: f64, period: f64, scale: f64) -> SinSignal {
         SinSignal {
             x: 0.0,
+            offset: 0.0,
             interval,
             period,
             scale,
         }
     }
+
+    // Keep x small for long sessions; the offset only matters modulo a whole
+    // period, so wrap it too instead of letting the sine argument grow.
+    pub fn shift(&mut self, dx: f64) {
+        self.x -= dx;
+        self.offset = (self.offset + dx).rem_euclid(self.period);
+    }
 }
 
 impl Iterator for SinSignal {
@@ -51,18 +174,169 @@ impl Iterator for SinSignal {
     //     Some(point)
     // }
     fn next(&mut self) -> Option<Self::Item> {
-        let adjusted_x = self.x - WINDOW_SIZE as f64; // x から 3600 を減算して調整
+        // every wave starts at zero and completes one cycle per `period`
         let point = if self.x < 0.0 {
             (self.x, 0.0)
         } else {
-            (self.x, (adjusted_x * 2.0 * std::f64::consts::PI / self.period).sin() * self.scale)
+            (
+                self.x,
+                ((self.x + self.offset) * 2.0 * std::f64::consts::PI / self.period).sin()
+                    * self.scale,
+            )
         };
         self.x += self.interval;
         Some(point)
     }
 }
 
+// 'h': the latest sessions from the log, newest first
+struct HistoryView {
+    records: Vec<history::SessionRecord>,
+    selected: usize,
+}
+
+impl HistoryView {
+    // move the selection, stopping at the first and last rows
+    fn scroll(&mut self, delta: isize) {
+        let last = self.records.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+}
+
+// how a logged phase came to an end; only one that finished counts as a pomodoro
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Ending {
+    // ran out, or was skipped
+    Finished,
+    // quit or killed, so the next launch can offer to resume it
+    Interrupted,
+    // thrown away with reset
+    Abandoned,
+}
+
 struct App {
+    history: Option<PathBuf>,
+    errors: Vec<String>,
+    // --set-title, and what the title was last set from
+    set_title: bool,
+    title_key: Option<(String, u64, bool)>,
+    // shared with the --serve thread
+    status: Option<Arc<Mutex<serve::Status>>>,
+    // mirrored here for --print-once if asked, rewritten when the second in
+    // `state_at` passes
+    state_file: Option<PathBuf>,
+    state_at: u64,
+    notify: bool,
+    bell: bool,
+    // how `bell` is sounded; swapped out in tests
+    beep: fn(Phase),
+    #[cfg(feature = "sound")]
+    sound: Option<PathBuf>,
+    // where finished work sessions are POSTed, alongside the history log
+    #[cfg(feature = "webhook")]
+    webhook: Option<String>,
+    mute_after: Option<clock::TimeOfDay>,
+    show_help: bool,
+    keys: KeyMap,
+    // ask before 'q' throws away a phase in progress
+    confirm_quit: bool,
+    quit_prompt: bool,
+    // a phase cut short by the last run, offered on startup: (phase, secs left)
+    resume_prompt: Option<(Phase, u64)>,
+    // the phase under way carries on one the last run cut short
+    resumed: bool,
+    history_view: Option<HistoryView>,
+    // --strict locks the keys that cut a phase short; --strict-pause adds pausing
+    strict: bool,
+    strict_pause: bool,
+    // a short message for the status line and when it goes away
+    toast: Option<(String, Instant)>,
+    show_chart: bool,
+    // smaller than this, ask for a bigger terminal instead of drawing
+    min_size: (u16, u16),
+    // something on screen changed since the last draw
+    dirty: bool,
+    // --at: when the first pomodoro starts by itself, and the second the
+    // countdown to it last showed
+    start_at: Option<u64>,
+    start_shown: u64,
+    // 'z': nothing but the countdown in big digits
+    zen: bool,
+    // which of the three waves to draw, in dataset order
+    visible: [bool; 3],
+    show_stats: bool,
+    // pomodoros to aim for today; 0 for no goal
+    goal: u32,
+    // the status line's time, only reformatted when the second changes
+    clock_format: Option<clock::ClockFormat>,
+    clock_text: String,
+    clock_at: u64,
+    now_color: Color,
+    marker: symbols::Marker,
+    // per wave, in `Phase::ALL` order; --colorblind tells them apart by shape
+    wave_markers: Option<[symbols::Marker; 3]>,
+    palette: theme::Palette,
+    // fixed UI words in the --lang language
+    strings: &'static Strings,
+    // the current phase fades from the first to the second as it runs out
+    urgency_colors: Option<[(u8, u8, u8); 2]>,
+    task: String,
+    break_tips: Vec<String>,
+    // --micro-activities: the list, and the one this break drew if any
+    micro_activities: Vec<String>,
+    activity: Option<usize>,
+    rng: rng::Rng,
+    // the tip for the current break; moves on as each break starts
+    tip: Option<usize>,
+    // logged with the current work session
+    interruptions: u32,
+    // 'u': this work session won't finish the task, so the next one picks it
+    // up again, marked with ↻
+    unfinished: bool,
+    carried_over: bool,
+    // display names, in `Phase::ALL` order
+    labels: [String; 3],
+    deep_work_minutes: u64,
+    // configured notification bodies for the end of each phase
+    end_messages: [Option<String>; 3],
+    // end times of finished work sessions, for the per-hour sparkline
+    work_ends: Vec<u64>,
+    sparkline_hours: usize,
+    hourly: Vec<u64>,
+    hourly_at: u64,
+    // Per-day figures from the log, for the day in `today`: work finished
+    // before the current session, and the run of days with a pomodoro.
+    today: clock::Date,
+    // when `today` began, and how many pomodoros the timer had done by then
+    day_start: u64,
+    completed_before_today: u32,
+    focus_today: u64,
+    pomodoros_today: u32,
+    // summed `history::focus_weight` of today's pomodoros
+    focus_weight_today: f64,
+    day_streak: u32,
+    worked_today: bool,
+    // --auto-pause-after: pause work after this long without a key or mouse event
+    auto_pause_after: Option<Duration>,
+    auto_paused: bool,
+    // false until the first pause key press under --start-paused
+    begun: bool,
+    // Some while the task name is being edited
+    task_input: Option<String>,
+    // --notes holds back each finished work session's record until its note
+    // has been typed, along with the note so far
+    notes: bool,
+    note_input: Option<(history::SessionRecord, String)>,
+    timer: Timer,
+    // seconds the timer has run since launch, not counting pauses
+    session_secs: u64,
+    // the default schedule first, then any from the config; 'n' cycles them
+    schedules: Vec<Schedule>,
+    schedule: usize,
+    // (chart x, minutes focused today), one point per minute of work;
+    // trimmed to the visible window and cleared at midnight
+    focus_line: Vec<(f64, f64)>,
+    show_focus_line: bool,
     signal1: SinSignal,
     data1: Vec<(f64, f64)>,
     signal2: SinSignal,
@@ -70,29 +344,422 @@ struct App {
     signal3: SinSignal,
     data3: Vec<(f64, f64)>,
     window: [f64; 2],
+    // samples across the chart
+    window_size: usize,
+    // how far the next tick is, in [0, 1); the chart slides by this much
+    // between ticks so it glides instead of stepping
+    tick_fraction: f64,
 }
 
 impl App {
-    fn new() -> App {
+    fn new(work: u64, short_break: u64, long_break: u64, window_size: usize) -> App {
         let one_minutes = 60.0;
-        let mut signal1 = SinSignal::new(1.0, one_minutes*5.0, 18.0); // 5min
-        let mut signal2 = SinSignal::new(1.0, one_minutes * 25.0, 15.0); // 25min
-        let mut signal3 = SinSignal::new(1.0, one_minutes * 30.0, 10.0); // 30min
-        let data1 = signal1.by_ref().take(WINDOW_SIZE).collect::<Vec<(f64, f64)>>();
-        let data2 = signal2.by_ref().take(WINDOW_SIZE).collect::<Vec<(f64, f64)>>();
-        let data3 = signal3.by_ref().take(WINDOW_SIZE).collect::<Vec<(f64, f64)>>();
+        let mut signal1 = SinSignal::new(1.0, one_minutes * short_break as f64, 18.0);
+        let mut signal2 = SinSignal::new(1.0, one_minutes * work as f64, 15.0);
+        let mut signal3 = SinSignal::new(1.0, one_minutes * long_break as f64, 10.0);
+        let data1 = signal1
+            .by_ref()
+            .take(window_size)
+            .collect::<Vec<(f64, f64)>>();
+        let data2 = signal2
+            .by_ref()
+            .take(window_size)
+            .collect::<Vec<(f64, f64)>>();
+        let data3 = signal3
+            .by_ref()
+            .take(window_size)
+            .collect::<Vec<(f64, f64)>>();
         App {
+            history: None,
+            errors: Vec::new(),
+            set_title: false,
+            title_key: None,
+            status: None,
+            state_file: None,
+            state_at: 0,
+            notify: true,
+            bell: false,
+            beep: notify::beep,
+            #[cfg(feature = "sound")]
+            sound: None,
+            #[cfg(feature = "webhook")]
+            webhook: None,
+            mute_after: None,
+            show_help: false,
+            keys: KeyMap::default(),
+            confirm_quit: true,
+            quit_prompt: false,
+            resume_prompt: None,
+            resumed: false,
+            history_view: None,
+            strict: false,
+            strict_pause: false,
+            toast: None,
+            show_chart: true,
+            min_size: (MIN_WIDTH, MIN_HEIGHT),
+            dirty: true,
+            start_at: None,
+            start_shown: 0,
+            zen: false,
+            visible: [true; 3],
+            show_stats: false,
+            goal: 0,
+            clock_format: None,
+            clock_text: String::new(),
+            clock_at: 0,
+            now_color: Color::White,
+            marker: symbols::Marker::Braille,
+            wave_markers: None,
+            palette: theme::Palette::default(),
+            strings: i18n::english(),
+            urgency_colors: None,
+            task: String::new(),
+            break_tips: BREAK_TIPS.iter().map(|tip| tip.to_string()).collect(),
+            micro_activities: Vec::new(),
+            activity: None,
+            rng: rng::Rng::from_time(),
+            tip: None,
+            interruptions: 0,
+            unfinished: false,
+            carried_over: false,
+            labels: Phase::ALL.map(|phase| phase.label().to_string()),
+            end_messages: Default::default(),
+            deep_work_minutes: DEFAULT_DEEP_WORK_MINUTES,
+            work_ends: Vec::new(),
+            sparkline_hours: DEFAULT_SPARKLINE_HOURS,
+            hourly: Vec::new(),
+            hourly_at: 0,
+            today: clock::today(),
+            day_start: clock::day_start(clock::unix_now()),
+            completed_before_today: 0,
+            focus_today: 0,
+            pomodoros_today: 0,
+            focus_weight_today: 0.0,
+            day_streak: 0,
+            worked_today: false,
+            task_input: None,
+            auto_pause_after: None,
+            auto_paused: false,
+            notes: false,
+            note_input: None,
+            begun: true,
+            schedules: Vec::new(),
+            schedule: 0,
+            session_secs: 0,
+            timer: Timer::new(work, short_break, long_break, DEFAULT_POMODOROS_PER_SET),
+            focus_line: Vec::new(),
+            show_focus_line: false,
             signal1,
             data1,
             signal2,
             data2,
             signal3,
             data3,
-            window: [0.0, WINDOW_SIZE as f64],
+            window: [0.0, window_size as f64],
+            window_size,
+            tick_fraction: 0.0,
+        }
+    }
+
+    fn use_schedule(&mut self, index: usize) {
+        self.schedule = index;
+        self.timer.set_schedule(&self.schedules[index]);
+        self.reset_chart();
+    }
+
+    fn next_schedule(&mut self) {
+        if self.schedules.len() > 1 {
+            self.use_schedule((self.schedule + 1) % self.schedules.len());
+        }
+    }
+
+    fn reset(&mut self) {
+        // log an abandoned work session so its interruptions aren't lost
+        if let Some(cut) = self.cut_short().filter(|t| t.from == Phase::Work) {
+            self.record_transition(cut, Ending::Abandoned);
+        }
+        self.timer.reset();
+        self.unfinished = false;
+        self.carried_over = false;
+        self.completed_before_today = 0;
+        self.reset_chart();
+    }
+
+    // restart the chart without touching the phase clock
+    fn reset_chart(&mut self) {
+        let fresh = App::new(
+            self.timer.phase_secs(Phase::Work) / 60,
+            self.timer.phase_secs(Phase::ShortBreak) / 60,
+            self.timer.phase_secs(Phase::LongBreak) / 60,
+            self.window_size,
+        );
+        self.signal1 = fresh.signal1;
+        self.data1 = fresh.data1;
+        self.signal2 = fresh.signal2;
+        self.data2 = fresh.data2;
+        self.signal3 = fresh.signal3;
+        self.data3 = fresh.data3;
+        let shift = self.window[1] - fresh.window[1];
+        for point in self.focus_line.iter_mut() {
+            point.0 -= shift;
+        }
+        self.window = fresh.window;
+        self.trim_focus_line();
+    }
+
+    // Keep one point left of the window so the line still enters from the edge.
+    fn trim_focus_line(&mut self) {
+        let hidden = self
+            .focus_line
+            .iter()
+            .take_while(|point| point.0 < self.window[0])
+            .count();
+        self.focus_line.drain(..hidden.saturating_sub(1));
+    }
+
+    // Move the chart back by a whole window once it has scrolled that far,
+    // reusing the sample buffers and leaving the timer state alone.
+    fn cycle_window(&mut self) {
+        let shift = self.window_size as f64;
+        for data in [&mut self.data1, &mut self.data2, &mut self.data3] {
+            for point in data.iter_mut() {
+                point.0 -= shift;
+            }
+        }
+        for signal in [&mut self.signal1, &mut self.signal2, &mut self.signal3] {
+            signal.shift(shift);
+        }
+        for point in self.focus_line.iter_mut() {
+            point.0 -= shift;
+        }
+        self.window[0] -= shift;
+        self.window[1] -= shift;
+        self.trim_focus_line();
+    }
+
+    // the chart's x range as drawn, slid along by the part of a tick that's passed
+    fn view_window(&self) -> [f64; 2] {
+        [
+            self.window[0] + self.tick_fraction,
+            self.window[1] + self.tick_fraction,
+        ]
+    }
+
+    fn update_clock(&mut self) {
+        let Some(format) = self.clock_format else {
+            return;
+        };
+        let now = clock::unix_now();
+        if now != self.clock_at {
+            self.clock_text = clock::local(now).format(format);
+            self.clock_at = now;
+            self.dirty = true;
+        }
+    }
+
+    // Begin now whatever is being held for --at or --manual.
+    fn start_now(&mut self) {
+        self.start_at = None;
+        self.timer.start_next();
+        self.begun = true;
+    }
+
+    // true when the --at time has come and the timer started
+    fn check_start_at(&mut self) -> bool {
+        let Some(at) = self.start_at else {
+            return false;
+        };
+        let now = clock::unix_now();
+        if now >= at {
+            log::info!("--at time reached, starting");
+            self.start_now();
+            self.dirty = true;
+            return true;
+        }
+        if now != self.start_shown {
+            self.start_shown = now;
+            self.dirty = true;
+        }
+        false
+    }
+
+    fn is_too_small(&self, size: Rect) -> bool {
+        size.width < self.min_size.0 || size.height < self.min_size.1
+    }
+
+    fn expire_toast(&mut self) {
+        if self
+            .toast
+            .as_ref()
+            .is_some_and(|(_, until)| Instant::now() >= *until)
+        {
+            self.toast = None;
+            self.dirty = true;
+        }
+    }
+
+    // The chart scrolls smoothly between ticks, so it needs every frame while
+    // the clock runs; everything else only changes on a tick or an event.
+    fn is_animating(&self) -> bool {
+        self.timer.is_running() && self.show_chart && !self.zen && self.history_view.is_none()
+    }
+
+    fn current_status(&self) -> serve::Status {
+        serve::Status {
+            phase: self.timer.current_phase(),
+            label: self.phase_label().to_string(),
+            remaining_secs: self.timer.remaining_secs(),
+            completed: self.timer.completed(),
+            paused: !self.timer.is_running(),
+        }
+    }
+
+    // Hand the status to the --serve thread, and rewrite the state file for
+    // --print-once once a second.
+    fn publish_status(&mut self) {
+        if let Some(status) = &self.status {
+            *status.lock().unwrap_or_else(|e| e.into_inner()) = self.current_status();
+        }
+        let now = clock::unix_now();
+        if let Some(path) = self.state_file.as_ref().filter(|_| now != self.state_at) {
+            self.state_at = now;
+            if let Err(err) = state::write(path, &self.current_status(), now) {
+                let msg = format!("failed to write state to {}: {}", path.display(), err);
+                if !self.errors.contains(&msg) {
+                    self.errors.push(msg);
+                }
+            }
+        }
+    }
+
+    // what the title changes with: the phase, the minute of the countdown and
+    // the pause state
+    fn title_key(&self) -> (String, u64, bool) {
+        (
+            self.phase_label().to_string(),
+            self.timer.clock_secs().div_ceil(60),
+            self.timer.is_running(),
+        )
+    }
+
+    // Retitle the terminal when `title_key` changes rather than every frame.
+    fn update_title(&mut self) {
+        if !self.set_title {
+            return;
+        }
+        let key = self.title_key();
+        if self.title_key.as_ref() == Some(&key) {
+            return;
+        }
+        let title = format!(
+            "{}{} {} — pomodoro",
+            if key.2 { "" } else { "⏸ " },
+            key.0,
+            format_mmss(self.timer.clock_secs())
+        );
+        self.title_key = Some(key);
+        let _ = execute!(io::stdout(), SetTitle(title));
+    }
+
+    fn open_history_view(&mut self) {
+        let records = match self.history.as_deref().map(history::load) {
+            Some(Ok(records)) => records,
+            Some(Err(err)) => {
+                let text = Strings::fill(self.strings.history_unreadable, &err.to_string());
+                self.show_toast(&text);
+                return;
+            }
+            None => Vec::new(),
+        };
+        let skip = records.len().saturating_sub(HISTORY_VIEW_LIMIT);
+        self.history_view = Some(HistoryView {
+            records: records.into_iter().skip(skip).rev().collect(),
+            selected: 0,
+        });
+    }
+
+    fn on_history_key(&mut self, code: KeyCode) {
+        let Some(view) = &mut self.history_view else {
+            return;
+        };
+        match code {
+            KeyCode::Char('j') | KeyCode::Down => view.scroll(1),
+            KeyCode::Char('k') | KeyCode::Up => view.scroll(-1),
+            KeyCode::Esc | KeyCode::Char('h' | 'q') => self.history_view = None,
+            _ => {}
+        }
+    }
+
+    // a paused phase still counts, since quitting throws away the time it has run
+    fn is_mid_phase(&self) -> bool {
+        self.timer.elapsed() > 0 && !self.timer.is_finished()
+    }
+
+    // --strict only holds while a phase is under way
+    fn is_locked(&self) -> bool {
+        self.strict && !self.timer.is_finished() && !self.timer.is_awaiting()
+    }
+
+    fn show_toast(&mut self, text: &str) {
+        self.toast = Some((text.to_string(), Instant::now() + TOAST_DURATION));
+    }
+
+    fn show_locked(&mut self) {
+        self.show_toast(&format!("🔒 {}", self.strings.locked));
+    }
+
+    // the current phase's gauge and wave, fading with `urgency_colors`
+    fn active_color(&self) -> Color {
+        match self.urgency_colors {
+            Some([from, to]) => {
+                let remaining = 1.0 - self.timer.progress();
+                theme::blend(from, to, remaining)
+            }
+            None => self.palette.phase(self.timer.current_phase()),
+        }
+    }
+
+    fn label(&self, phase: Phase) -> &str {
+        &self.labels[phase.index()]
+    }
+
+    fn wave_marker(&self, phase: Phase) -> symbols::Marker {
+        self.wave_markers
+            .map_or(self.marker, |markers| markers[phase.index()])
+    }
+
+    // name of the phase under way, which may be deep work rather than a scheduled one
+    fn phase_label(&self) -> &str {
+        if self.timer.is_deep_work() {
+            self.strings.deep_work
+        } else {
+            self.label(self.timer.current_phase())
+        }
+    }
+
+    fn outcome(&self) -> Outcome {
+        Outcome {
+            sets_completed: self.timer.sets_completed(),
+            finished: self.timer.is_finished(),
         }
     }
 
-    fn on_tick(&mut self) {
+    fn on_tick(&mut self) -> Option<Transition> {
+        if !self.timer.is_running() {
+            return None;
+        }
+        self.dirty = true;
+        if clock::today() != self.today {
+            let records = self
+                .history
+                .as_deref()
+                .map(history::load)
+                .and_then(Result::ok)
+                .unwrap_or_default();
+            self.refresh_today(&records);
+        }
+        let transition = self.timer.tick();
+        self.session_secs += 1;
         self.data1.remove(0);
         self.data1.extend(self.signal1.by_ref().take(1));
         self.data2.remove(0);
@@ -101,118 +768,2760 @@ impl App {
         self.data3.extend(self.signal3.by_ref().take(1));
         self.window[0] += 1.0;
         self.window[1] += 1.0;
+        if transition.is_none()
+            && self.timer.current_phase() == Phase::Work
+            && self.timer.elapsed().is_multiple_of(60)
+        {
+            let minutes = self.focus_today_secs() as f64 / 60.0;
+            self.focus_line.push((self.window[1] - 1.0, minutes));
+        }
+        if clock::unix_now() / 3600 != self.hourly_at {
+            self.refresh_hourly();
+        }
+        transition
     }
-}
 
-fn main() -> Result<(), Box<dyn Error>> {
-    // setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    // Jump within the current phase; never crosses into another phase.
+    fn seek(&mut self, secs: i64) {
+        let delta = self.timer.seek(secs);
+        self.scroll_chart(delta as f64);
+    }
 
-    // create app and run it
-    let tick_rate = Duration::from_millis(1000);
-    let app = App::new();
-    let res = run_app(&mut terminal, app, tick_rate);
+    // Move the chart by `delta` samples, regenerating the visible window.
+    fn scroll_chart(&mut self, delta: f64) {
+        for (signal, data) in [
+            (&mut self.signal1, &mut self.data1),
+            (&mut self.signal2, &mut self.data2),
+            (&mut self.signal3, &mut self.data3),
+        ] {
+            signal.x += delta * signal.interval;
+            let mut window = signal.clone();
+            window.x -= self.window_size as f64 * window.interval;
+            data.clear();
+            data.extend(window.take(self.window_size));
+        }
+        self.window[0] += delta;
+        self.window[1] += delta;
+    }
 
-    // restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    // end the current phase early, counting it as if it had run out
+    fn skip(&mut self) -> Transition {
+        let transition = self.timer.skip();
+        self.reset_chart();
+        transition
+    }
 
-    if let Err(err) = res {
-        println!("{:?}", err)
+    // Typing while the task is being edited; every key is literal text.
+    fn on_input_key(&mut self, key: KeyEvent) {
+        let Some(input) = self.task_input.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                self.task = input.trim().to_string();
+                self.task_input = None;
+            }
+            KeyCode::Esc => self.task_input = None,
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) => input.push(c),
+            _ => {}
+        }
     }
 
-    Ok(())
-}
+    fn on_note_key(&mut self, key: KeyEvent) {
+        let Some((_, note)) = self.note_input.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => self.save_note(),
+            KeyCode::Esc => {
+                note.clear();
+                self.save_note();
+            }
+            KeyCode::Backspace => {
+                note.pop();
+            }
+            KeyCode::Char(c) => note.push(c),
+            _ => {}
+        }
+    }
 
-fn run_app<B: Backend>(
-    terminal: &mut Terminal<B>,
-    mut app: App,
-    tick_rate: Duration,
-) -> io::Result<()> {
-    let mut last_tick = Instant::now();
-    loop {
-        terminal.draw(|f| ui::<B>(f, &app))?;
+    // Hold the timer while asking whether to carry on where the last run left off.
+    fn offer_resume(&mut self, records: &[history::SessionRecord]) {
+        let Some(record) = history::resumable(records, clock::unix_now()) else {
+            return;
+        };
+        let Some(phase) = Phase::from_key(&record.phase) else {
+            return;
+        };
+        self.resume_prompt = Some((phase, record.planned_secs - record.actual_secs));
+        if !self.timer.is_paused() {
+            self.timer.toggle_pause();
+        }
+    }
 
-        let timeout = tick_rate
-            .checked_sub(last_tick.elapsed())
-            .unwrap_or_else(|| Duration::from_secs(0));
-        if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                if let KeyCode::Char('q') = key.code {
-                    return Ok(());
+    fn answer_resume(&mut self, resume: bool) {
+        if let Some((phase, remaining)) = self.resume_prompt.take() {
+            if resume {
+                self.timer.resume(phase, remaining);
+                self.resumed = true;
+                self.reset_chart();
+            }
+            // under --start-paused it waits for the pause key as usual
+            if self.begun && self.timer.is_paused() {
+                self.timer.toggle_pause();
+            }
+        }
+    }
+
+    fn load_history(&mut self, records: &[history::SessionRecord]) {
+        self.work_ends = records
+            .iter()
+            .filter(|r| r.is_pomodoro())
+            .map(|r| r.end())
+            .collect();
+        self.refresh_hourly();
+        self.refresh_today(records);
+    }
+
+    // Worked out from the log once per day rather than every frame;
+    // transitions keep them current in between.
+    fn refresh_today(&mut self, records: &[history::SessionRecord]) {
+        let now = clock::unix_now();
+        self.today = clock::local(now).date;
+        self.day_start = clock::day_start(now);
+        self.completed_before_today = self.timer.completed();
+        let summary = history::summarize(history::on_date(records, self.today));
+        self.focus_today = summary.focus_secs;
+        self.pomodoros_today = summary.pomodoros;
+        self.focus_weight_today = summary.focus_weight;
+        self.worked_today = summary.pomodoros > 0;
+        self.day_streak = history::day_streak(records, self.today);
+        self.focus_line.clear();
+    }
+
+    // the part of `secs` of work ending now that falls on today, for a
+    // session that started before midnight
+    fn worked_since_midnight(&self, secs: u64) -> u64 {
+        secs.min(clock::unix_now().saturating_sub(self.day_start))
+    }
+
+    fn focus_today_secs(&self) -> u64 {
+        match self.timer.current_phase() {
+            Phase::Work => self.focus_today + self.worked_since_midnight(self.timer.elapsed()),
+            Phase::ShortBreak | Phase::LongBreak => self.focus_today,
+        }
+    }
+
+    // Only called when a session is logged or the hour rolls over, not per frame.
+    fn refresh_hourly(&mut self) {
+        let now = clock::unix_now();
+        let horizon = now.saturating_sub(self.sparkline_hours as u64 * 3600);
+        self.work_ends.retain(|&end| end >= horizon);
+        self.hourly = history::per_hour(&self.work_ends, now, self.sparkline_hours);
+        self.hourly_at = now / 3600;
+    }
+
+    // checked at every transition, since a session can run past the threshold
+    fn is_quiet_hours(&self) -> bool {
+        self.mute_after.is_some_and(|from| {
+            clock::local(clock::unix_now())
+                .time_of_day()
+                .is_between(from, QUIET_HOURS_END)
+        })
+    }
+
+    fn on_transition(&mut self, transition: Transition) {
+        self.record_transition(transition, Ending::Finished);
+        self.announce_transition(transition);
+        if transition.to != Phase::Work {
+            self.next_tip();
+        }
+    }
+
+    fn next_tip(&mut self) {
+        if !self.break_tips.is_empty() {
+            self.tip = Some(self.tip.map_or(0, |i| (i + 1) % self.break_tips.len()));
+        }
+        self.activity = None;
+        if !self.micro_activities.is_empty() && self.rng.below(MICRO_ACTIVITY_ODDS) == 0 {
+            self.activity = Some(self.rng.below(self.micro_activities.len()));
+        }
+    }
+
+    // the break's micro-activity and its seconds left, for its first half minute
+    fn current_activity(&self) -> Option<(&str, u64)> {
+        if self.timer.current_phase() == Phase::Work {
+            return None;
+        }
+        let left = MICRO_ACTIVITY_SECS.checked_sub(self.timer.elapsed())?;
+        let activity = self.micro_activities.get(self.activity?)?;
+        Some((activity, left))
+    }
+
+    // shown during breaks only
+    fn current_tip(&self) -> Option<&str> {
+        if self.timer.current_phase() == Phase::Work {
+            return None;
+        }
+        self.tip
+            .and_then(|i| self.break_tips.get(i))
+            .map(String::as_str)
+    }
+
+    // After a suspend: move the clock on without replaying every tick on the
+    // chart, and only announce the phase we woke up in.
+    fn catch_up(&mut self, ticks: u64) {
+        let transitions = self.timer.fast_forward(ticks);
+        self.reset_chart();
+        for &transition in &transitions {
+            self.record_transition(transition, Ending::Finished);
+        }
+        if let Some(&last) = transitions.last() {
+            self.announce_transition(last);
+            if last.to != Phase::Work {
+                self.next_tip();
+            }
+        }
+    }
+
+    // the phase in progress as if it ended now, for logging it unfinished
+    fn cut_short(&self) -> Option<Transition> {
+        let phase = self.timer.current_phase();
+        (self.timer.elapsed() > 0).then(|| Transition {
+            from: phase,
+            to: phase,
+            started: self.timer.phase_started(),
+            elapsed: self.timer.elapsed(),
+            planned: if self.timer.is_stopwatch() {
+                self.timer.elapsed()
+            } else {
+                self.timer.current_secs()
+            },
+            paused_secs: self.timer.paused_secs(),
+            deep_work: self.timer.is_deep_work(),
+        })
+    }
+
+    // quit or killed: keep what there is of the current phase so the next
+    // launch can offer to resume it
+    fn on_quit(&mut self) {
+        if let Some(cut) = self.cut_short() {
+            self.record_transition(cut, Ending::Interrupted);
+        }
+    }
+
+    fn record_transition(&mut self, transition: Transition, ending: Ending) {
+        log::info!(
+            "transition {:?} -> {:?} after {}s of {}s, completed={}",
+            transition.from,
+            transition.to,
+            transition.elapsed,
+            transition.planned,
+            self.timer.completed()
+        );
+        // deep work and a session cut short count as focus, not as pomodoros
+        if transition.deep_work || (transition.from == Phase::Work && ending != Ending::Finished) {
+            self.focus_today += self.worked_since_midnight(transition.elapsed);
+        } else if transition.from == Phase::Work {
+            self.work_ends.push(transition.started + transition.elapsed);
+            self.refresh_hourly();
+            self.focus_today += self.worked_since_midnight(transition.elapsed);
+            // the log files a session under the day it started
+            if transition.started >= self.day_start {
+                self.pomodoros_today += 1;
+                self.focus_weight_today += history::focus_weight(
+                    transition.elapsed,
+                    transition.planned,
+                    self.interruptions,
+                );
+                if !self.worked_today {
+                    self.worked_today = true;
+                    self.day_streak += 1;
                 }
             }
         }
-        if last_tick.elapsed() >= tick_rate {
-            app.on_tick();
-            if app.window[1] == 3600.0 {
-                app = App::new();
+        // a note still being typed belongs to an earlier session
+        self.save_note();
+        let phase = if transition.deep_work {
+            history::DEEP_WORK
+        } else {
+            transition.from.key()
+        };
+        let record = history::SessionRecord {
+            phase: phase.to_string(),
+            start: transition.started,
+            planned_secs: transition.planned,
+            actual_secs: transition.elapsed,
+            wall_secs: transition.elapsed + transition.paused_secs,
+            task: Some(self.task.clone()).filter(|t| !t.is_empty()),
+            interruptions: self.interruptions,
+            interrupted: ending == Ending::Interrupted,
+            abandoned: ending == Ending::Abandoned,
+            resumed: self.resumed && !transition.deep_work,
+            unfinished: self.unfinished && transition.from == Phase::Work,
+            note: None,
+        };
+        if self.notes && transition.from == Phase::Work && ending == Ending::Finished {
+            self.note_input = Some((record, String::new()));
+        } else {
+            self.write_record(&record);
+        }
+        self.interruptions = 0;
+        if !transition.deep_work {
+            self.resumed = false;
+        }
+        if transition.from == Phase::Work && !transition.deep_work {
+            self.carried_over = self.unfinished;
+            self.unfinished = false;
+        }
+    }
+
+    fn toggle_unfinished(&mut self) {
+        self.unfinished = !self.unfinished;
+        let text = if self.unfinished {
+            format!("↻ {}", self.strings.marked_unfinished)
+        } else {
+            self.strings.unmarked_unfinished.to_string()
+        };
+        self.show_toast(&text);
+    }
+
+    // The webhook and the log each get the record on their own, so a run
+    // without the log, e.g. under --dry-run, still posts it.
+    fn write_record(&mut self, record: &history::SessionRecord) {
+        #[cfg(feature = "webhook")]
+        self.post_record(record);
+        let Some(path) = &self.history else {
+            return;
+        };
+        if let Err(err) = history::append(path, record) {
+            self.disable_history(err);
+        }
+    }
+
+    // only pomodoros are posted, not breaks or sessions cut short
+    #[cfg(feature = "webhook")]
+    fn post_record(&self, record: &history::SessionRecord) {
+        if let Some(url) = self.webhook.as_deref().filter(|_| record.is_pomodoro()) {
+            webhook::post(url, record);
+        }
+    }
+
+    // Carry on without the log after a write fails, saying so once rather
+    // than failing again on every transition; today's stats keep counting
+    // this run's sessions.
+    fn disable_history(&mut self, err: io::Error) {
+        let Some(path) = self.history.take() else {
+            return;
+        };
+        log::warn!("history disabled: {}: {}", path.display(), err);
+        self.errors.push(format!(
+            "history disabled, failed to write {}: {}",
+            path.display(),
+            err
+        ));
+        self.toast = Some((
+            Strings::fill(self.strings.history_disabled, &err.kind().to_string()),
+            Instant::now() + WARNING_DURATION,
+        ));
+    }
+
+    // Write the held-back record with whatever note has been typed so far.
+    fn save_note(&mut self) {
+        if let Some((mut record, note)) = self.note_input.take() {
+            record.note = Some(note.trim().to_string()).filter(|n| !n.is_empty());
+            self.write_record(&record);
+        }
+    }
+
+    fn announce_transition(&mut self, transition: Transition) {
+        // a stopwatch only ever stops on a key press
+        if self.timer.is_stopwatch() {
+            return;
+        }
+        if self.is_quiet_hours() {
+            log::debug!("quiet hours, not notifying");
+            return;
+        }
+        if self.notify {
+            let summary = match transition.from {
+                _ if transition.deep_work => self.strings.deep_work_done,
+                Phase::Work => self.strings.work_done,
+                Phase::ShortBreak | Phase::LongBreak => self.strings.break_over,
+            };
+            let body = match &self.end_messages[transition.from.index()] {
+                Some(template) => notify::fill(
+                    template,
+                    &[
+                        ("completed", &self.pomodoros_today.to_string()),
+                        ("task", &self.task),
+                        ("next_phase", self.label(transition.to)),
+                        ("duration", &format_mmss(transition.elapsed)),
+                    ],
+                ),
+                None => Strings::fill_all(
+                    self.strings.next_up,
+                    &[
+                        self.label(transition.to),
+                        &(self.timer.planned_secs() / 60).to_string(),
+                    ],
+                ),
+            };
+            notify::send(summary, &body);
+            if transition.from == Phase::Work && self.goal > 0 && self.pomodoros_today == self.goal
+            {
+                notify::send(
+                    self.strings.goal_reached,
+                    &Strings::fill(self.strings.goal_done, &self.goal.to_string()),
+                );
             }
-            last_tick = Instant::now();
+        }
+        self.ring(transition.from);
+    }
+
+    fn ring(&mut self, ended: Phase) {
+        #[cfg(feature = "sound")]
+        if let Some(path) = &self.sound {
+            match sound::play(path) {
+                Ok(()) => return,
+                Err(err) => {
+                    let msg = format!(
+                        "cannot play {}: {}; using the terminal bell",
+                        path.display(),
+                        err
+                    );
+                    log::warn!("{}", msg);
+                    if !self.errors.contains(&msg) {
+                        self.errors.push(msg);
+                    }
+                    (self.beep)(ended);
+                    return;
+                }
+            }
+        }
+        if self.bell {
+            (self.beep)(ended);
         }
     }
 }
 
-fn ui<B: Backend>(f: &mut Frame, app: &App) {
-    let size = f.size();
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Ratio(1, 1)].as_ref())
-        .split(size);
-    let x_labels = vec![
-        Span::styled(
-            format!("{}", app.signal1.x),
-            Style::default().add_modifier(Modifier::BOLD),
-        ),
-        Span::raw(format!("Pomodoro")),
-        Span::styled(
-            format!(
-                "{:0>2}:{}",
-                (app.window[1] % 1800.0 / 60.0).floor(),
-                (app.window[1] % 60.0).floor()
-            ),
-            Style::default().add_modifier(Modifier::BOLD),
-        ),
-    ];
-    let datasets = vec![
-        Dataset::default()
-            .name("Break")
-            .marker(symbols::Marker::Braille)
-            .style(Style::default().fg(Color::Cyan))
-            .data(&app.data1),
-        Dataset::default()
-            .name("Work")
-            .marker(symbols::Marker::Braille)
-            .style(Style::default().fg(Color::Red))
-            .data(&app.data2),
-        Dataset::default()
-            .name("Lunch")
-            .marker(symbols::Marker::Braille)
-            .style(Style::default().fg(Color::Yellow))
-            .data(&app.data3),
-    ];
+// How long --at waits from now; None once that time has come today.
+fn start_delay(now: &clock::LocalTime, at: clock::TimeOfDay) -> Option<u64> {
+    (at > now.time_of_day()).then(|| clock::secs_until(now, at))
+}
 
-    let chart = Chart::new(datasets)
-        .block(Block::default())
-        .x_axis(
-            Axis::default()
-                .style(Style::default().fg(Color::Gray))
-                .labels(x_labels)
-                .bounds(app.window),
-        )
-        .y_axis(
-            Axis::default()
-                .style(Style::default().fg(Color::Gray))
-                .bounds([-20.0, 20.0]),
-        );
-    f.render_widget(chart, chunks[0]);
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = match cli::parse(std::env::args().skip(1)) {
+        Ok(cli::Command::Run(args)) => *args,
+        Ok(cli::Command::Help) => {
+            print!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Ok(cli::Command::Version) => {
+            println!("{}", version());
+            return Ok(());
+        }
+        Ok(cli::Command::Summary { json }) => return print_summary(json),
+        Ok(cli::Command::PrintOnce { state_file }) => {
+            let status = state_file
+                .or_else(state::default_path)
+                .and_then(|path| state::read(&path, clock::unix_now()));
+            println!("{}", state::line(status.as_ref()));
+            return Ok(());
+        }
+        Ok(cli::Command::ExportCsv(path)) => {
+            if let Err(err) = export_csv(&path) {
+                eprintln!("error: {}", err);
+                process::exit(EXIT_ERROR);
+            }
+            return Ok(());
+        }
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, cli::USAGE);
+            process::exit(EXIT_ERROR);
+        }
+    };
+
+    let config = match args.config {
+        Some(ref path) => config::load(path, true),
+        None => match config::default_path() {
+            Some(path) => config::load(&path, false),
+            None => Ok(config::Config::default()),
+        },
+    };
+    let mut config = match config {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(EXIT_ERROR);
+        }
+    };
+    let env_warnings = config::apply_env(&mut config);
+    let work = args
+        .work
+        .or(config.work_minutes)
+        .unwrap_or(DEFAULT_WORK_MINUTES);
+    let short_break = args
+        .short_break
+        .or(config.short_break_minutes)
+        .unwrap_or(DEFAULT_SHORT_BREAK_MINUTES);
+    let long_break = args
+        .long_break
+        .or(config.long_break_minutes)
+        .unwrap_or(DEFAULT_LONG_BREAK_MINUTES);
+
+    let default = Schedule {
+        name: "default".to_string(),
+        work,
+        short_break,
+        long_break,
+        pomodoros_per_set: args
+            .pomodoros_per_set
+            .or(config.pomodoros_per_set)
+            .unwrap_or(DEFAULT_POMODOROS_PER_SET),
+    };
+    let mut schedules = vec![default.clone()];
+    for named in config.schedules {
+        schedules.push(Schedule {
+            name: named.name,
+            work: named.work_minutes.unwrap_or(default.work),
+            short_break: named.short_break_minutes.unwrap_or(default.short_break),
+            long_break: named.long_break_minutes.unwrap_or(default.long_break),
+            pomodoros_per_set: named.pomodoros_per_set.unwrap_or(default.pomodoros_per_set),
+        });
+    }
+    let selected = match &args.schedule {
+        Some(name) => match schedules.iter().position(|s| &s.name == name) {
+            Some(index) => index,
+            None => {
+                let names: Vec<&str> = schedules.iter().map(|s| s.name.as_str()).collect();
+                eprintln!(
+                    "error: unknown schedule '{}': expected one of {}",
+                    name,
+                    names.join(", ")
+                );
+                process::exit(EXIT_ERROR);
+            }
+        },
+        None => 0,
+    };
+
+    if let Some(path) = &args.log_file {
+        if let Err(err) = logger::init(path) {
+            eprintln!("error: cannot open log file {}: {}", path.display(), err);
+            process::exit(EXIT_ERROR);
+        }
+    }
+
+    #[cfg(not(feature = "sound"))]
+    if args.sound.is_some() {
+        eprintln!("error: --sound needs a build with `--features sound`");
+        process::exit(EXIT_ERROR);
+    }
+    #[cfg(not(feature = "webhook"))]
+    if args.webhook.is_some() {
+        eprintln!("error: --webhook needs a build with `--features webhook`");
+        process::exit(EXIT_ERROR);
+    }
+
+    if let Err(err) = check_terminal(|| io::stdout().is_terminal()) {
+        eprintln!("error: {}", err);
+        process::exit(EXIT_ERROR);
+    }
+
+    // bind before taking over the terminal so a busy port is a plain error
+    let mut server = None;
+    let status = Arc::new(Mutex::new(serve::Status {
+        phase: Phase::Work,
+        label: String::new(),
+        remaining_secs: 0,
+        completed: 0,
+        paused: false,
+    }));
+    if let Some(addr) = args.serve {
+        match serve::Server::start(addr, Arc::clone(&status)) {
+            Ok(started) => server = Some(started),
+            Err(err) => {
+                eprintln!("error: cannot serve on {}: {}", addr, err);
+                process::exit(EXIT_ERROR);
+            }
+        }
+    }
+
+    // set by SIGTERM/SIGINT from outside; run_app checks it every iteration
+    let terminate = Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    for signal in [signal_hook::consts::SIGTERM, signal_hook::consts::SIGINT] {
+        signal_hook::flag::register(signal, Arc::clone(&terminate))?;
+    }
+
+    // setup terminal
+    let mouse = !args.no_mouse;
+    install_panic_hook(mouse, args.set_title);
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    if mouse {
+        execute!(stdout, EnableMouseCapture)?;
+    }
+    if args.set_title {
+        execute!(stdout, Print(PUSH_TITLE))?;
+    }
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    // create app and run it
+    let mut tick_rate =
+        Duration::from_millis(args.tick_ms.or(config.tick_ms).unwrap_or(DEFAULT_TICK_MS));
+    if args.dry_run {
+        tick_rate /= DRY_RUN_SPEEDUP;
+    }
+    let mut app = App::new(
+        work,
+        short_break,
+        long_break,
+        args.window_secs.unwrap_or(DEFAULT_WINDOW_SECS),
+    );
+    app.schedules = schedules;
+    app.use_schedule(selected);
+    // a --plan stands in for the schedules, so 'n' has nothing to switch to
+    if let Some(plan) = args.plan {
+        app.schedules.truncate(1);
+        app.timer.set_plan(plan);
+        app.reset_chart();
+    }
+    app.notify = !args.no_notify && config.notifications.unwrap_or(true);
+    app.bell = args.bell;
+    #[cfg(feature = "sound")]
+    {
+        app.sound = args.sound;
+    }
+    #[cfg(feature = "webhook")]
+    {
+        app.webhook = args.webhook;
+    }
+    app.set_title = args.set_title;
+    app.strict = args.strict;
+    app.strict_pause = args.strict_pause;
+    app.confirm_quit = !args.no_confirm || args.strict;
+    app.mute_after = args.mute_after;
+    app.timer.set_manual(args.manual);
+    app.timer.set_stopwatch(args.stopwatch);
+    if let Some(at) = args.at {
+        let now = clock::unix_now();
+        if let Some(delay) = start_delay(&clock::local(now), at) {
+            app.start_at = Some(now + delay);
+            app.timer.hold();
+        }
+    }
+    app.auto_pause_after = args.auto_pause_after.map(Duration::from_secs);
+    if args.start_paused {
+        app.timer.toggle_pause();
+        app.begun = false;
+    }
+    app.timer.set_limit(args.loop_sets);
+    if let Some(palette) = args.theme.or(config.theme) {
+        app.palette = palette;
+    }
+    if args.colorblind {
+        app.palette = theme::Palette::named("colorblind").unwrap_or_default();
+        app.wave_markers = Some(COLORBLIND_MARKERS);
+    }
+    app.show_chart = !args.no_chart;
+    // a smaller minimum is taken as given; the layout just squeezes the
+    // gauge and chart into whatever rows there are
+    app.min_size = (
+        config.min_width.unwrap_or(MIN_WIDTH),
+        config.min_height.unwrap_or(MIN_HEIGHT),
+    );
+    app.show_stats = args.show_stats;
+    app.goal = args.goal.unwrap_or(0);
+    app.clock_format = args.clock;
+    app.task = args.task.unwrap_or_default();
+    app.notes = args.notes;
+    // only on request, since a second instance would fight over the file
+    app.state_file = args
+        .state_file
+        .clone()
+        .or_else(|| state::default_path().filter(|_| config.share_state));
+    app.urgency_colors = config.urgency_colors;
+    if let Some(tips) = config.break_tips {
+        app.break_tips = tips;
+    }
+    if args.micro_activities {
+        app.micro_activities = config
+            .micro_activities
+            .unwrap_or_else(|| MICRO_ACTIVITIES.iter().map(|a| a.to_string()).collect());
+    }
+    if let Some(seed) = args.seed {
+        app.rng = rng::Rng::new(seed);
+    }
+    app.keys = config.keys;
+    if let Some(lang) = args.lang.or(config.lang) {
+        match i18n::strings(&lang) {
+            Some(strings) => app.strings = strings,
+            None => app.errors.push(format!(
+                "unknown language '{}', using English (have {})",
+                lang,
+                i18n::NAMES.join(", ")
+            )),
+        }
+    }
+    app.labels = app.strings.phases.map(str::to_string);
+    for (label, (arg, config)) in app
+        .labels
+        .iter_mut()
+        .zip(args.labels.into_iter().zip(config.labels))
+    {
+        if let Some(text) = arg.or(config).filter(|text| !text.is_empty()) {
+            *label = text;
+        }
+    }
+    app.end_messages = config.end_messages;
+    app.deep_work_minutes = args
+        .deep_work
+        .or(config.deep_work_minutes)
+        .unwrap_or(DEFAULT_DEEP_WORK_MINUTES);
+    if let Some(marker) = args.marker {
+        app.marker = marker;
+    }
+    if let Some(color) = args.now_color.or(config.now_color) {
+        app.now_color = color;
+    }
+    // a dry run is a rehearsal, so keep it out of the history log
+    app.history = history::default_path().filter(|_| !args.dry_run);
+    if server.is_some() {
+        app.status = Some(status);
+    }
+    app.errors.extend(env_warnings);
+    app.sparkline_hours = args.sparkline_hours.unwrap_or(DEFAULT_SPARKLINE_HOURS);
+    match app.history.as_deref().map(history::load) {
+        Some(Ok(records)) => {
+            app.load_history(&records);
+            app.offer_resume(&records);
+        }
+        Some(Err(err)) => app.errors.push(format!("failed to read history: {}", err)),
+        None => app.refresh_hourly(),
+    }
+    if let Some(Err(err)) = app.history.as_deref().map(history::check_writable) {
+        app.disable_history(err);
+    }
+    let res = run_app(&mut terminal, &mut app, tick_rate, &terminate);
+    app.on_quit();
+    app.save_note();
+
+    // restore terminal
+    disable_raw_mode()?;
+    if mouse {
+        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    }
+    if app.set_title {
+        execute!(terminal.backend_mut(), Print(POP_TITLE))?;
+    }
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    if let Some(server) = server {
+        server.shutdown();
+    }
+    if let Some(path) = &app.state_file {
+        state::remove(path);
+    }
+
+    for err in &app.errors {
+        eprintln!("warning: {}", err);
+    }
+    if let Ok(Outcome { finished: true, .. }) = res {
+        println!(
+            "Finished {} set(s): {} pomodoros.",
+            app.timer.sets_finished(),
+            app.timer.completed()
+        );
+    }
+    match res {
+        Ok(outcome) if outcome.sets_completed > 0 => process::exit(EXIT_SET_COMPLETED),
+        Ok(_) => process::exit(EXIT_QUIT_EARLY),
+        Err(err) => {
+            println!("{:?}", err);
+            process::exit(EXIT_ERROR);
+        }
+    }
+}
+
+struct Outcome {
+    sets_completed: u32,
+    // every set --loop asked for was done
+    finished: bool,
+}
+
+// "pomodoro 0.1.0 (abc1234)", or without the commit outside a git checkout
+fn version() -> String {
+    let version = format!("pomodoro {}", env!("CARGO_PKG_VERSION"));
+    match option_env!("POMODORO_GIT_COMMIT") {
+        Some(commit) => format!("{} ({})", version, commit),
+        None => version,
+    }
+}
+
+// Raw mode and the alternate screen make no sense when piped or run headless.
+fn check_terminal(is_terminal: impl FnOnce() -> bool) -> Result<(), &'static str> {
+    if is_terminal() {
+        Ok(())
+    } else {
+        Err("pomodoro needs an interactive terminal; use --summary for a plain-text report")
+    }
+}
+
+// Put the terminal back before the panic message is printed, otherwise it
+// lands on the alternate screen and the shell is left in raw mode.
+fn install_panic_hook(mouse: bool, set_title: bool) {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = disable_raw_mode();
+        if mouse {
+            let _ = execute!(io::stdout(), DisableMouseCapture);
+        }
+        if set_title {
+            let _ = execute!(io::stdout(), Print(POP_TITLE));
+        }
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, Show);
+        default_hook(info);
+    }));
+}
+
+fn print_summary(json: bool) -> Result<(), Box<dyn Error>> {
+    let records = match history::default_path() {
+        Some(path) => history::load(&path)?,
+        None => Vec::new(),
+    };
+    let today = clock::today();
+    let summary = history::summarize(history::on_date(&records, today));
+    if json {
+        println!("{}", summary.to_json(today));
+        return Ok(());
+    }
+    if summary.pomodoros == 0 {
+        println!("No sessions recorded today.");
+        return Ok(());
+    }
+    println!("Today ({})", today);
+    println!("  Pomodoros       {:>5}", summary.pomodoros);
+    println!("  Focus minutes   {:>5}", summary.focus_secs / 60);
+    println!("  Longest streak  {:>5}", summary.longest_streak);
+    if let Some(score) = history::focus_score(summary.focus_weight, summary.pomodoros) {
+        println!("  Focus score     {:>5}", score);
+    }
+    Ok(())
+}
+
+fn export_csv(path: &Path) -> Result<(), Box<dyn Error>> {
+    let records = match history::default_path() {
+        Some(history) => history::load(&history)?,
+        None => Vec::new(),
+    };
+    let file = fs::File::create(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    history::write_csv(io::BufWriter::new(file), &records)?;
+    Ok(())
+}
+
+// Terminals with the keyboard enhancement protocol also report releases and
+// auto-repeats. Releases never do anything; repeats only make sense for
+// seeking and for typing, everything else would toggle back and forth.
+fn is_actionable(key: &KeyEvent, typing: bool) -> bool {
+    match key.kind {
+        KeyEventKind::Press => true,
+        KeyEventKind::Repeat => typing || matches!(key.code, KeyCode::Left | KeyCode::Right),
+        KeyEventKind::Release => false,
+    }
+}
+
+// Raw mode turns Ctrl-C into an ordinary key press, so handle it ourselves.
+fn is_ctrl_c(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    tick_rate: Duration,
+    terminate: &AtomicBool,
+) -> io::Result<Outcome> {
+    let mut last_tick = Instant::now();
+    let mut last_render: Option<Instant> = None;
+    let mut last_toggle: Option<Instant> = None;
+    // how far into the current tick we were when paused
+    let mut paused_offset = Duration::ZERO;
+    // Instant stops during suspend on Linux and macOS while the wall clock
+    // keeps going, so compare the two to notice the lid being closed
+    let mut last_iteration = (Instant::now(), SystemTime::now());
+    let mut last_activity = Instant::now();
+    loop {
+        if terminate.load(Ordering::Relaxed) {
+            log::info!("terminated by a signal");
+            return Ok(app.outcome());
+        }
+        app.publish_status();
+        app.update_title();
+        app.update_clock();
+        app.expire_toast();
+        if app.check_start_at() {
+            last_tick = Instant::now();
+            paused_offset = Duration::ZERO;
+        }
+        let frame_due = last_render.is_none_or(|t| t.elapsed() >= RENDER_INTERVAL);
+        if app.dirty || (app.is_animating() && frame_due) {
+            app.tick_fraction = if app.timer.is_running() {
+                tick_fraction(last_tick.elapsed(), tick_rate)
+            } else if app.timer.is_paused() {
+                tick_fraction(paused_offset, tick_rate)
+            } else {
+                0.0
+            };
+            terminal.draw(|f| ui(f, app))?;
+            app.dirty = false;
+            last_render = Some(Instant::now());
+        }
+
+        // when nothing animates, still look in now and then for the clock
+        // line and toasts running out
+        let until_render = match last_render {
+            Some(t) if app.is_animating() => RENDER_INTERVAL.saturating_sub(t.elapsed()),
+            _ => RENDER_INTERVAL,
+        };
+        let timeout = if !app.timer.is_running() {
+            until_render
+        } else {
+            until_render.min(tick_rate.saturating_sub(last_tick.elapsed()))
+        };
+        if crossterm::event::poll(timeout)? {
+            // show the effect of a key press right away
+            app.dirty = true;
+            let mut event = event::read()?;
+            if matches!(event, Event::Key(_) | Event::Mouse(_)) {
+                last_activity = Instant::now();
+                // coming back from idle only resumes, whatever was pressed
+                if app.auto_paused {
+                    app.auto_paused = false;
+                    app.timer.toggle_pause();
+                    last_tick = Instant::now() - paused_offset;
+                    continue;
+                }
+            }
+            // a click on the chart stands in for the pause key, or toggles a wave
+            if let Event::Mouse(mouse) = event {
+                let prompting = app.quit_prompt
+                    || app.resume_prompt.is_some()
+                    || app.history_view.is_some()
+                    || app.task_input.is_some()
+                    || app.note_input.is_some();
+                if mouse.kind == MouseEventKind::Down(MouseButton::Left) && !prompting {
+                    match click_target(app, terminal.size()?, mouse.column, mouse.row) {
+                        Some(Click::Pause) => {
+                            let pause = KeyCode::Char(app.keys.char(Action::Pause));
+                            event = Event::Key(KeyEvent::new(pause, KeyModifiers::NONE));
+                        }
+                        Some(Click::Wave(i)) => app.visible[i] = !app.visible[i],
+                        None => {}
+                    }
+                }
+            }
+            if let Event::Key(key) = event {
+                log::debug!("key {:?}", key);
+            }
+            match event {
+                Event::Key(key)
+                    if !is_actionable(
+                        &key,
+                        app.task_input.is_some() || app.note_input.is_some(),
+                    ) => {}
+                Event::Key(key) if app.quit_prompt => match key.code {
+                    _ if is_ctrl_c(&key) => return Ok(app.outcome()),
+                    KeyCode::Char('y') => return Ok(app.outcome()),
+                    KeyCode::Char('n') | KeyCode::Esc => app.quit_prompt = false,
+                    _ => {}
+                },
+                Event::Key(key) if app.resume_prompt.is_some() => match key.code {
+                    _ if is_ctrl_c(&key) => return Ok(app.outcome()),
+                    KeyCode::Char(c @ ('y' | 'n')) => {
+                        app.answer_resume(c == 'y');
+                        last_tick = Instant::now();
+                        paused_offset = Duration::ZERO;
+                    }
+                    KeyCode::Esc => {
+                        app.answer_resume(false);
+                        last_tick = Instant::now();
+                        paused_offset = Duration::ZERO;
+                    }
+                    _ => {}
+                },
+                Event::Key(key) if app.history_view.is_some() => {
+                    if is_ctrl_c(&key) {
+                        return Ok(app.outcome());
+                    }
+                    app.on_history_key(key.code);
+                }
+                Event::Key(key) if app.note_input.is_some() => {
+                    if is_ctrl_c(&key) {
+                        return Ok(app.outcome());
+                    }
+                    app.on_note_key(key);
+                }
+                Event::Key(key) if app.task_input.is_some() => {
+                    if is_ctrl_c(&key) {
+                        return Ok(app.outcome());
+                    }
+                    app.on_input_key(key);
+                }
+                Event::Key(key) => match app.keys.action(key.code) {
+                    _ if is_ctrl_c(&key) => return Ok(app.outcome()),
+                    Some(Action::Quit) if app.confirm_quit && app.is_mid_phase() => {
+                        app.quit_prompt = true;
+                    }
+                    Some(Action::Quit) => return Ok(app.outcome()),
+                    // once finished only quitting and resetting do anything
+                    Some(Action::Pause | Action::Skip) if app.timer.is_finished() => {}
+                    Some(Action::Skip | Action::Reset) if app.is_locked() => {
+                        app.show_locked();
+                    }
+                    Some(Action::Pause)
+                        if app.strict_pause
+                            && app.is_locked()
+                            && app.begun
+                            && !app.timer.is_paused() =>
+                    {
+                        app.show_locked();
+                    }
+                    Some(Action::Pause) if app.start_at.is_some() => {
+                        app.start_now();
+                        last_tick = Instant::now();
+                        paused_offset = Duration::ZERO;
+                    }
+                    Some(Action::Help) => app.show_help = !app.show_help,
+                    Some(Action::Reset) => {
+                        app.reset();
+                        last_tick = Instant::now();
+                        paused_offset = Duration::ZERO;
+                    }
+                    Some(Action::Skip) => {
+                        let transition = app.skip();
+                        app.on_transition(transition);
+                        last_tick = Instant::now();
+                        paused_offset = Duration::ZERO;
+                    }
+                    Some(Action::Pause) if is_debounced(&mut last_toggle, Instant::now()) => {
+                        if app.timer.is_paused() {
+                            last_tick = Instant::now() - paused_offset;
+                        } else {
+                            paused_offset = last_tick.elapsed().min(tick_rate);
+                        }
+                        app.timer.toggle_pause();
+                        app.begun = true;
+                    }
+                    Some(Action::Pause) => {}
+                    None => match key.code {
+                        KeyCode::Enter | KeyCode::Char('n' | 'i' | 'd' | 'u')
+                            if app.timer.is_finished() => {}
+                        KeyCode::Left | KeyCode::Right | KeyCode::Char('n' | 'd')
+                            if app.is_locked() =>
+                        {
+                            app.show_locked();
+                        }
+                        KeyCode::Char('e') => app.task_input = Some(app.task.clone()),
+                        KeyCode::Left => app.seek(-SEEK_SECS),
+                        KeyCode::Right => app.seek(SEEK_SECS),
+                        KeyCode::Char(c @ '1'..='3') => {
+                            let i = c as usize - '1' as usize;
+                            app.visible[i] = !app.visible[i];
+                        }
+                        KeyCode::Char('f') => app.show_focus_line = !app.show_focus_line,
+                        KeyCode::Char('z') => app.zen = !app.zen,
+                        KeyCode::Char('h') => app.open_history_view(),
+                        KeyCode::Esc => app.show_help = false,
+                        KeyCode::Enter if app.timer.is_awaiting() => {
+                            app.start_now();
+                            last_tick = Instant::now();
+                            paused_offset = Duration::ZERO;
+                        }
+                        KeyCode::Char('u')
+                            if app.timer.current_phase() == Phase::Work
+                                && !app.timer.is_deep_work() =>
+                        {
+                            app.toggle_unfinished();
+                        }
+                        KeyCode::Char('i') if app.timer.current_phase() == Phase::Work => {
+                            app.interruptions += 1;
+                        }
+                        KeyCode::Char('+' | '-' | ']' | '[') if app.is_locked() => {
+                            app.show_locked();
+                        }
+                        KeyCode::Char(c @ ('+' | '-')) => {
+                            let remaining = app.timer.extend(if c == '+' { 1 } else { -1 });
+                            let text = format!(
+                                "{}: {} {}",
+                                app.phase_label(),
+                                format_mmss(remaining),
+                                app.strings.left
+                            );
+                            app.show_toast(&text);
+                        }
+                        // "capital" + and - for the lasting change don't exist:
+                        // + is already shift-= and shift-- is _ on most
+                        // layouts, so it sits on the brackets beside them
+                        KeyCode::Char(c @ (']' | '[')) => {
+                            let minutes = app.timer.adjust_plan(if c == ']' { 1 } else { -1 });
+                            let text = format!(
+                                "{}: {}",
+                                app.phase_label(),
+                                Strings::fill(app.strings.from_now_on, &minutes.to_string())
+                            );
+                            app.show_toast(&text);
+                        }
+                        KeyCode::Char('n') => {
+                            app.next_schedule();
+                            last_tick = Instant::now();
+                            paused_offset = Duration::ZERO;
+                        }
+                        KeyCode::Char('d') if app.timer.start_deep_work(app.deep_work_minutes) => {
+                            app.begun = true;
+                            last_tick = Instant::now();
+                            paused_offset = Duration::ZERO;
+                        }
+                        _ => {}
+                    },
+                },
+                Event::Resize(width, height) => {
+                    log::debug!("resize {}x{}", width, height);
+                }
+                _ => {}
+            }
+        }
+        if app.timer.is_running()
+            && app.timer.current_phase() == Phase::Work
+            && is_idle(last_activity.elapsed(), app.auto_pause_after)
+        {
+            log::info!("no input for {:?}, pausing", last_activity.elapsed());
+            paused_offset = last_tick.elapsed().min(tick_rate);
+            app.timer.toggle_pause();
+            app.auto_paused = true;
+            app.dirty = true;
+        }
+        let wall = last_iteration.1.elapsed().unwrap_or_default();
+        let slept = wall.saturating_sub(last_iteration.0.elapsed());
+        last_iteration = (Instant::now(), SystemTime::now());
+        if slept >= SUSPEND_THRESHOLD && app.timer.is_running() {
+            let ticks = (slept.as_secs_f64() / tick_rate.as_secs_f64()) as u64;
+            log::info!(
+                "wall clock jumped {:?} ahead, catching up {} ticks",
+                slept,
+                ticks
+            );
+            app.catch_up(ticks);
+        }
+        // step from the previous tick rather than from now so the phase
+        // clock doesn't drift by however late this iteration ran
+        while app.timer.is_running() && last_tick.elapsed() >= tick_rate {
+            log::trace!(
+                "tick {:?} elapsed={}s late_by={:?}",
+                app.timer.current_phase(),
+                app.timer.elapsed() + 1,
+                last_tick.elapsed() - tick_rate
+            );
+            if let Some(transition) = app.on_tick() {
+                app.on_transition(transition);
+            }
+            while app.window[1] >= 2.0 * app.window_size as f64 {
+                app.cycle_window();
+            }
+            last_tick += tick_rate;
+        }
+    }
+}
+
+// Whether a press at `now` is far enough from the last one to act on. Every
+// press moves `last` on, ignored ones too, so a held key's stream of repeats
+// is ignored as a whole rather than toggling once per debounce interval.
+fn is_debounced(last: &mut Option<Instant>, now: Instant) -> bool {
+    let ready = last.is_none_or(|t| now.saturating_duration_since(t) >= TOGGLE_DEBOUNCE);
+    *last = Some(now);
+    ready
+}
+
+fn is_idle(since_input: Duration, limit: Option<Duration>) -> bool {
+    limit.is_some_and(|limit| since_input >= limit)
+}
+
+fn tick_fraction(since_tick: Duration, tick_rate: Duration) -> f64 {
+    (since_tick.as_secs_f64() / tick_rate.as_secs_f64()).clamp(0.0, 1.0 - f64::EPSILON)
+}
+
+fn format_mmss(seconds: u64) -> String {
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn format_hhmmss(seconds: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+fn session_text(app: &App) -> String {
+    format!(
+        "{}: {}",
+        app.strings.session,
+        format_hhmmss(app.session_secs)
+    )
+}
+
+// the optional clock line, then everything else
+fn screen_layout(app: &App, size: Rect) -> Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(if app.clock_format.is_some() { 1 } else { 0 }),
+                Constraint::Min(0),
+            ]
+            .as_ref(),
+        )
+        .split(size)
+}
+
+// gauge, goal, break tip, sparkline and chart, top to bottom
+fn chart_layout(app: &App, area: Rect) -> Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(3),
+                Constraint::Length(if app.goal > 0 { 1 } else { 0 }),
+                Constraint::Length(if tip_line(app).is_some() { 1 } else { 0 }),
+                Constraint::Length(if app.sparkline_hours > 0 { 3 } else { 0 }),
+                Constraint::Min(0),
+            ]
+            .as_ref(),
+        )
+        .split(area)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Click {
+    Pause,
+    // a wave's legend entry, by index into `Phase::ALL`
+    Wave(usize),
+}
+
+// What a left click at (column, row) does: toggling a wave from its legend
+// entry, or pausing anywhere else on the chart. The legend's place follows
+// ratatui's own layout for a chart with no y-axis labels.
+fn click_target(app: &App, size: Rect, column: u16, row: u16) -> Option<Click> {
+    if app.is_too_small(size) {
+        return None;
+    }
+    if app.zen {
+        return Some(Click::Pause);
+    }
+    let body = screen_layout(app, size)[1];
+    if !app.show_chart || body.width < CHART_MIN_WIDTH {
+        return None;
+    }
+    let chart = chart_layout(app, body)[4];
+    if !chart.intersects(Rect::new(column, row, 1, 1)) {
+        return None;
+    }
+    // below the title row and above the x-axis and its labels
+    let graph = Rect::new(
+        chart.x,
+        chart.y + 1,
+        chart.width,
+        chart.height.saturating_sub(3),
+    );
+    let waves: Vec<usize> = (0..Phase::ALL.len()).filter(|&i| app.visible[i]).collect();
+    let mut names: Vec<&str> = waves.iter().map(|&i| app.labels[i].as_str()).collect();
+    // the unnamed "now" marker still takes a row
+    names.push("");
+    if app.show_focus_line {
+        names.push(app.strings.focus);
+    }
+    let legend_width = names
+        .iter()
+        .map(|&name| Span::raw(name).width() as u16)
+        .max()
+        .unwrap_or(0)
+        + 2;
+    let legend_height = names.len() as u16 + 2;
+    if legend_width < graph.width / 4
+        && legend_height < graph.height / 4
+        && column >= graph.right() - legend_width
+    {
+        let entry = usize::from(row.saturating_sub(graph.y + 1));
+        if row > graph.y && entry < waves.len() {
+            return Some(Click::Wave(waves[entry]));
+        }
+    }
+    Some(Click::Pause)
+}
+
+fn ui(f: &mut Frame, app: &App) {
+    let size = f.size();
+    if app.is_too_small(size) {
+        let size_needed = format!("{}x{}", app.min_size.0, app.min_size.1);
+        let text = Strings::fill(app.strings.enlarge, &size_needed);
+        let height = (text.len() as u16).div_ceil(size.width.max(1));
+        let message = Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+        f.render_widget(message, centered_rect(size.width, height, size));
+        return;
+    }
+    if let Some(view) = &app.history_view {
+        render_history_view(f, app, view, size);
+    } else if app.zen {
+        render_zen(f, app, size);
+    } else {
+        render_body(f, app, size);
+    }
+    if app.show_help {
+        render_help(f, app, size);
+    }
+    if let Some((_, note)) = &app.note_input {
+        render_note_prompt(f, app.strings, note, size);
+    }
+    if let Some((phase, remaining)) = app.resume_prompt {
+        render_resume_prompt(f, app.strings, app.label(phase), remaining, size);
+    }
+    if app.quit_prompt {
+        render_quit_prompt(f, app.strings, size);
+    }
+}
+
+fn render_body(f: &mut Frame, app: &App, size: Rect) {
+    let chunks = screen_layout(app, size);
+    if app.clock_format.is_some() {
+        let status = Paragraph::new(Span::styled(
+            app.clock_text.as_str(),
+            Style::default().fg(app.palette.label),
+        ))
+        .alignment(Alignment::Right);
+        f.render_widget(status, chunks[0]);
+    }
+    if app.show_chart && chunks[1].width >= CHART_MIN_WIDTH {
+        render_chart(f, app, chunks[1]);
+    } else {
+        render_text(f, app, chunks[1]);
+    }
+}
+
+// Five-row block digits for the zen view, plus the colon.
+const BIG_GLYPH_HEIGHT: u16 = 5;
+
+fn big_glyph(c: char) -> [&'static str; 5] {
+    match c {
+        '0' => ["█████", "█   █", "█   █", "█   █", "█████"],
+        '1' => ["   █ ", "  ██ ", "   █ ", "   █ ", "  ███"],
+        '2' => ["█████", "    █", "█████", "█    ", "█████"],
+        '3' => ["█████", "    █", " ████", "    █", "█████"],
+        '4' => ["█   █", "█   █", "█████", "    █", "    █"],
+        '5' => ["█████", "█    ", "█████", "    █", "█████"],
+        '6' => ["█████", "█    ", "█████", "█   █", "█████"],
+        '7' => ["█████", "    █", "   █ ", "  █  ", "  █  "],
+        '8' => ["█████", "█   █", "█████", "█   █", "█████"],
+        '9' => ["█████", "█   █", "█████", "    █", "█████"],
+        ':' => ["   ", " █ ", "   ", " █ ", "   "],
+        _ => ["     "; 5],
+    }
+}
+
+// `text` in big glyphs, one string per row, with a column between glyphs
+fn big_text(text: &str) -> Vec<String> {
+    (0..BIG_GLYPH_HEIGHT as usize)
+        .map(|row| {
+            text.chars()
+                .map(|c| big_glyph(c)[row])
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+// How many times over `big_text` fits in `area`, each glyph cell becoming a
+// square of that many columns and rows.
+fn zen_scale(width: u16, height: u16, area: Rect) -> u16 {
+    if width == 0 {
+        return 1;
+    }
+    (area.width / width).min(area.height / height).max(1)
+}
+
+fn render_zen(f: &mut Frame, app: &App, area: Rect) {
+    let rows = big_text(&format_mmss(app.timer.clock_secs()));
+    let width = rows
+        .iter()
+        .map(|row| row.chars().count())
+        .max()
+        .unwrap_or(0) as u16;
+    let scale = zen_scale(width, BIG_GLYPH_HEIGHT, area);
+    let style = Style::default().fg(app.palette.phase(app.timer.current_phase()));
+    let text: Vec<Line> = rows
+        .iter()
+        .flat_map(|row| {
+            let wide: String = row
+                .chars()
+                .flat_map(|c| std::iter::repeat_n(c, usize::from(scale)))
+                .collect();
+            std::iter::repeat_n(Line::from(Span::styled(wide, style)), usize::from(scale))
+        })
+        .collect();
+    f.render_widget(
+        Paragraph::new(text),
+        centered_rect(width * scale, BIG_GLYPH_HEIGHT * scale, area),
+    );
+}
+
+// what the app is doing right now: editing, paused, or the current task
+fn status_span(app: &App) -> Span<'_> {
+    if let Some(input) = &app.task_input {
+        Span::styled(
+            format!("{}: {}▏", app.strings.task, input),
+            Style::default().add_modifier(Modifier::REVERSED),
+        )
+    } else if let Some((text, _)) = app
+        .toast
+        .as_ref()
+        .filter(|(_, until)| Instant::now() < *until)
+    {
+        Span::styled(text.as_str(), Style::default().add_modifier(Modifier::BOLD))
+    } else if app.timer.is_finished() {
+        Span::styled(
+            Strings::fill(app.strings.all_done, &app.keys.name(Action::Quit)),
+            Style::default().add_modifier(Modifier::BOLD),
+        )
+    } else if let Some(at) = app.start_at {
+        let left = at.saturating_sub(clock::unix_now());
+        let left = if left >= 3600 {
+            format_hhmmss(left)
+        } else {
+            format_mmss(left)
+        };
+        Span::styled(
+            Strings::fill(app.strings.starting_in, &left),
+            Style::default().add_modifier(Modifier::BOLD),
+        )
+    } else if app.timer.is_awaiting() {
+        Span::styled(
+            Strings::fill(app.strings.press_enter_to_start, app.phase_label()),
+            Style::default().add_modifier(Modifier::BOLD),
+        )
+    } else if !app.begun {
+        Span::styled(
+            Strings::fill(app.strings.press_to_begin, &app.keys.name(Action::Pause)),
+            Style::default().add_modifier(Modifier::BOLD),
+        )
+    } else if app.auto_paused {
+        Span::styled(
+            format!("⏸ {}", app.strings.auto_paused),
+            Style::default().add_modifier(Modifier::BOLD),
+        )
+    } else if app.timer.is_paused() {
+        Span::styled(
+            format!("⏸ {}", app.strings.paused),
+            Style::default().add_modifier(Modifier::BOLD),
+        )
+    } else if app.carried_over {
+        let task = if app.task.is_empty() {
+            app.strings.pomodoro
+        } else {
+            app.task.as_str()
+        };
+        Span::styled(
+            format!("↻ {}", task),
+            Style::default().fg(app.palette.phase(app.timer.current_phase())),
+        )
+    } else if !app.task.is_empty() {
+        Span::styled(
+            app.task.as_str(),
+            Style::default().fg(app.palette.phase(app.timer.current_phase())),
+        )
+    } else {
+        Span::styled(app.strings.pomodoro, Style::default().fg(app.palette.label))
+    }
+}
+
+fn render_text(f: &mut Frame, app: &App, area: Rect) {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let mut text = vec![
+        Line::from(Span::styled(
+            app.phase_label(),
+            bold.fg(app.palette.phase(app.timer.current_phase())),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(format_mmss(app.timer.clock_secs()), bold)),
+        Line::from(""),
+        Line::from(status_span(app)),
+        Line::from(tally_text(app)),
+        Line::from(session_text(app)),
+    ];
+    if !app.timer.is_finished() {
+        text.push(Line::from(Span::styled(
+            next_phase_text(app),
+            Style::default().add_modifier(Modifier::DIM),
+        )));
+    }
+    if let Some(long_break) = long_break_text(app).filter(|_| !app.timer.is_finished()) {
+        text.push(Line::from(long_break));
+    }
+    if app.goal > 0 {
+        text.push(goal_line(app));
+    }
+    if let Some(tip) = tip_line(app) {
+        text.push(tip);
+    }
+    if app.show_stats {
+        text.push(Line::from(stats_text(app)));
+    }
+    let height = text.len() as u16;
+    let paragraph = Paragraph::new(text).alignment(Alignment::Center);
+    f.render_widget(paragraph, centered_rect(area.width, height, area));
+}
+
+// how many of the goal's glyphs to fill, and how many to leave dim
+fn goal_split(done: u32, goal: u32) -> (u32, u32) {
+    let filled = done.min(goal);
+    (filled, goal - filled)
+}
+
+fn goal_line(app: &App) -> Line<'_> {
+    let (filled, empty) = goal_split(app.pomodoros_today, app.goal);
+    let color = if empty == 0 {
+        Color::Green
+    } else {
+        app.palette.work
+    };
+    Line::from(vec![
+        Span::styled("●".repeat(filled as usize), Style::default().fg(color)),
+        Span::styled(
+            "○".repeat(empty as usize),
+            Style::default().add_modifier(Modifier::DIM),
+        ),
+        Span::raw(format!(" {}/{}", app.pomodoros_today, app.goal)),
+    ])
+}
+
+// a micro-activity while its countdown runs, otherwise the break tip
+fn tip_line(app: &App) -> Option<Line<'_>> {
+    if let Some((activity, left)) = app.current_activity() {
+        return Some(Line::from(Span::styled(
+            format!("🏃 {} — {}s", activity, left),
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+    }
+    let tip = app.current_tip()?;
+    Some(Line::from(Span::styled(
+        format!("💡 {}", tip),
+        Style::default().add_modifier(Modifier::ITALIC),
+    )))
+}
+
+fn tally_text(app: &App) -> String {
+    let mut text = format!(
+        "🍅 x{} · {}: {:.0}%",
+        app.timer.completed() - app.completed_before_today,
+        app.strings.set,
+        app.timer.set_progress() * 100.0
+    );
+    // only worth naming once there's more than one to choose from
+    if app.schedules.len() > 1 {
+        text.push_str(" · ");
+        text.push_str(&app.schedules[app.schedule].name);
+    }
+    if app.is_locked() {
+        text.push_str(" · 🔒");
+    }
+    if app.interruptions > 0 {
+        text.push_str(&format!(" · ⚡{}", app.interruptions));
+    }
+    if app.day_streak > 0 {
+        let streak = Strings::fill(app.strings.day_streak, &app.day_streak.to_string());
+        text.push_str(&format!(" · 🔥 {}", streak));
+    }
+    text
+}
+
+fn next_phase_text(app: &App) -> String {
+    if app.timer.is_stopwatch() {
+        return Strings::fill(app.strings.stop_and_log, &app.keys.name(Action::Skip));
+    }
+    let next = app.timer.next_phase();
+    format!(
+        "{}: {} ({}m)",
+        app.strings.next,
+        app.label(next),
+        app.timer.next_secs() / 60
+    )
+}
+
+fn long_break_text(app: &App) -> Option<String> {
+    match app.timer.until_long_break()? {
+        1 => Some(app.strings.long_break_next.to_string()),
+        left => Some(Strings::fill(
+            app.strings.until_long_break,
+            &left.to_string(),
+        )),
+    }
+}
+
+fn stats_text(app: &App) -> String {
+    let minutes = app.focus_today_secs() / 60;
+    let mut text = if minutes < 60 {
+        format!(
+            "{}: {}m {}",
+            app.strings.today, minutes, app.strings.focused
+        )
+    } else {
+        format!(
+            "{}: {}h{:02}m {}",
+            app.strings.today,
+            minutes / 60,
+            minutes % 60,
+            app.strings.focused
+        )
+    };
+    if let Some(score) = history::focus_score(app.focus_weight_today, app.pomodoros_today) {
+        text.push_str(&format!(" · {} {}", app.strings.score, score));
+    }
+    text
+}
+
+// Drop labels that would overlap in `width` columns, keeping the countdown
+// longest. The axis spreads labels evenly, so each gets about a third, and
+// the first one also pushes the plot right so it's the first to go.
+fn x_labels<'a>(
+    counter: Span<'a>,
+    status: Span<'a>,
+    remaining: Span<'a>,
+    width: u16,
+) -> Vec<Span<'a>> {
+    let third = usize::from(width) / 3;
+    let fits = |span: &Span| span.width() < third;
+    if fits(&counter) && fits(&status) && fits(&remaining) {
+        vec![counter, status, remaining]
+    } else if fits(&status) && fits(&remaining) {
+        vec![Span::raw(""), status, remaining]
+    } else {
+        vec![Span::raw(""), remaining]
+    }
+}
+
+// Today's focus minutes squeezed into the chart's y range, topping out at the
+// next whole hour so the line keeps rising rather than hugging the ceiling.
+fn focus_line(app: &App, y_bounds: [f64; 2]) -> Vec<(f64, f64)> {
+    let now = (
+        app.view_window()[1] - 1.0,
+        app.focus_today_secs() as f64 / 60.0,
+    );
+    let top = ((now.1 / 60.0).floor() + 1.0) * 60.0;
+    let scale = (y_bounds[1] - y_bounds[0]) / top;
+    app.focus_line
+        .iter()
+        .chain([&now])
+        .map(|&(x, minutes)| (x, y_bounds[0] + minutes * scale))
+        .collect()
+}
+
+fn y_bounds(scales: impl Iterator<Item = f64>) -> [f64; 2] {
+    let tallest = scales.map(f64::abs).fold(0.0, f64::max);
+    if tallest > 0.0 {
+        [-tallest - Y_MARGIN, tallest + Y_MARGIN]
+    } else {
+        Y_BOUNDS
+    }
+}
+
+fn render_chart(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = chart_layout(app, area);
+    let clock = format_mmss(app.timer.clock_secs());
+    let gauge = Gauge::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(tally_text(app))
+                .title(
+                    Title::from(Span::styled(
+                        if app.timer.is_finished() {
+                            String::new()
+                        } else {
+                            next_phase_text(app)
+                        },
+                        Style::default().add_modifier(Modifier::DIM),
+                    ))
+                    .alignment(Alignment::Right),
+                )
+                .title(
+                    Title::from(
+                        long_break_text(app)
+                            .filter(|_| !app.timer.is_finished())
+                            .unwrap_or_default(),
+                    )
+                    .position(Position::Bottom)
+                    .alignment(Alignment::Right),
+                ),
+        )
+        .gauge_style(Style::default().fg(app.active_color()))
+        .ratio(app.timer.progress())
+        .label(format!(
+            "{} {}",
+            clock,
+            if app.timer.is_stopwatch() {
+                app.strings.elapsed
+            } else {
+                app.strings.remaining
+            }
+        ));
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Min(0),
+                Constraint::Length(if app.show_stats {
+                    Span::raw(stats_text(app)).width() as u16 + 4
+                } else {
+                    0
+                }),
+            ]
+            .as_ref(),
+        )
+        .split(chunks[0]);
+    f.render_widget(gauge, top[0]);
+    if app.show_stats {
+        let stats = Paragraph::new(stats_text(app))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(stats, top[1]);
+    }
+    if app.goal > 0 {
+        f.render_widget(
+            Paragraph::new(goal_line(app)).alignment(Alignment::Center),
+            chunks[1],
+        );
+    }
+    if let Some(tip) = tip_line(app) {
+        f.render_widget(Paragraph::new(tip).alignment(Alignment::Center), chunks[2]);
+    }
+    if app.sparkline_hours > 0 {
+        render_sparkline(f, app, chunks[3]);
+    }
+
+    let label_style = Style::default()
+        .fg(app.palette.label)
+        .add_modifier(Modifier::BOLD);
+    let x_labels = x_labels(
+        Span::styled(format!("{}", app.signal1.x), label_style),
+        status_span(app),
+        Span::styled(clock, label_style),
+        chunks[4].width,
+    );
+    let mut datasets = Phase::ALL
+        .iter()
+        .zip([&app.data1, &app.data2, &app.data3])
+        .zip(app.visible)
+        .filter(|&(_, visible)| visible)
+        .map(|((&phase, data), _)| {
+            let mut style = Style::default().fg(app.palette.phase(phase));
+            if phase == app.timer.current_phase() {
+                style = style.fg(app.active_color()).add_modifier(Modifier::BOLD);
+            }
+            Dataset::default()
+                .name(app.label(phase))
+                .marker(app.wave_marker(phase))
+                .style(style)
+                .data(data)
+        })
+        .collect::<Vec<_>>();
+    // a vertical line through the newest sample; it has no name so it stays
+    // out of the legend
+    let now = app.view_window()[1] - 1.0;
+    let y_bounds = y_bounds(
+        [&app.signal1, &app.signal2, &app.signal3]
+            .into_iter()
+            .zip(app.visible)
+            .filter(|&(_, visible)| visible)
+            .map(|(signal, _)| signal.scale),
+    );
+    let now_marker: Vec<(f64, f64)> = (0..=((y_bounds[1] - y_bounds[0]) * 2.0) as usize)
+        .map(|i| (now, y_bounds[0] + i as f64 * 0.5))
+        .collect();
+    datasets.push(
+        Dataset::default()
+            .marker(app.marker)
+            .style(Style::default().fg(app.now_color))
+            .data(&now_marker),
+    );
+    let focus_line = if app.show_focus_line {
+        focus_line(app, y_bounds)
+    } else {
+        Vec::new()
+    };
+    if !focus_line.is_empty() {
+        datasets.push(
+            Dataset::default()
+                .name(app.strings.focus)
+                .marker(app.marker)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(app.palette.label))
+                .data(&focus_line),
+        );
+    }
+
+    let chart = Chart::new(datasets)
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    app.phase_label(),
+                    Style::default()
+                        .fg(app.palette.phase(app.timer.current_phase()))
+                        .add_modifier(Modifier::BOLD),
+                ))
+                .title(
+                    Title::from(Span::styled(session_text(app), label_style))
+                        .alignment(Alignment::Right),
+                ),
+        )
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(app.palette.axis))
+                .labels(x_labels)
+                .bounds(app.view_window()),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(app.palette.axis))
+                .bounds(y_bounds),
+        );
+    f.render_widget(chart, chunks[4]);
+}
+
+fn render_sparkline(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default().borders(Borders::ALL).title(Strings::fill(
+        app.strings.sparkline_title,
+        &app.sparkline_hours.to_string(),
+    ));
+    if app.hourly.iter().all(|&n| n == 0) {
+        let placeholder = Paragraph::new(app.strings.no_pomodoros_yet)
+            .style(Style::default().fg(app.palette.axis))
+            .alignment(Alignment::Center)
+            .block(block);
+        f.render_widget(placeholder, area);
+        return;
+    }
+    let sparkline = Sparkline::default()
+        .block(block)
+        .style(Style::default().fg(app.palette.work))
+        .data(&app.hourly);
+    f.render_widget(sparkline, area);
+}
+
+// rebindable keys name their action instead of a key; each line's text is
+// the one at the same place in `Strings::help`
+const HELP_KEYS: [Result<Action, &str>; 18] = [
+    Ok(Action::Pause),
+    Ok(Action::Skip),
+    Err("enter"),
+    Err("←/→"),
+    Ok(Action::Reset),
+    Err("+/-"),
+    Err("]/["),
+    Err("n"),
+    Err("d"),
+    Err("e"),
+    Err("i"),
+    Err("u"),
+    Err("1/2/3"),
+    Err("f"),
+    Err("z"),
+    Err("h"),
+    Ok(Action::Help),
+    Ok(Action::Quit),
+];
+
+fn render_help(f: &mut Frame, app: &App, area: Rect) {
+    let text: Vec<Line> = HELP_KEYS
+        .iter()
+        .zip(app.strings.help)
+        .map(|(&key, action)| {
+            let key = match key {
+                Ok(action) => app.keys.name(action),
+                Err(key) => key.to_string(),
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{:>6}  ", key),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(action),
+            ])
+        })
+        .collect();
+    let width = text.iter().map(Line::width).max().unwrap_or(0) as u16 + 3;
+    let popup = Paragraph::new(text).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(app.strings.help_title)
+            .title_alignment(Alignment::Center),
+    );
+    let area = centered_rect(width, HELP_KEYS.len() as u16 + 2, area);
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn render_history_view(f: &mut Frame, app: &App, view: &HistoryView, area: Rect) {
+    let rows: Vec<Row> = view
+        .records
+        .iter()
+        .map(|record| {
+            let start = clock::local(record.start);
+            let phase = match Phase::from_key(&record.phase) {
+                Some(phase) => app.label(phase),
+                None if record.phase == history::DEEP_WORK => app.strings.deep_work,
+                None => record.phase.as_str(),
+            };
+            Row::new(vec![
+                format!("{} {:02}:{:02}", start.date, start.hour, start.minute),
+                phase.to_string(),
+                format_mmss(record.actual_secs),
+                record.task.clone().unwrap_or_default(),
+            ])
+        })
+        .collect();
+    let title = Strings::fill(app.strings.history_title, &view.records.len().to_string());
+    let widths = [
+        Constraint::Length(16),
+        Constraint::Length(10),
+        Constraint::Length(6),
+        Constraint::Percentage(100),
+    ];
+    let table = Table::new(rows)
+        .header(
+            Row::new(app.strings.history_columns.to_vec())
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .widths(&widths)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = TableState::default();
+    state.select(Some(view.selected).filter(|_| !view.records.is_empty()));
+    f.render_stateful_widget(table, area, &mut state);
+}
+
+fn render_resume_prompt(f: &mut Frame, strings: &Strings, label: &str, remaining: u64, area: Rect) {
+    let text = Strings::fill_all(strings.resume_prompt, &[label, &format_mmss(remaining)]);
+    let width = Span::raw(text.as_str()).width() as u16 + 4;
+    let popup = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    let area = centered_rect(width, 3, area);
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn render_quit_prompt(f: &mut Frame, strings: &Strings, area: Rect) {
+    let width = Span::raw(strings.quit_prompt).width() as u16 + 6;
+    let popup = Paragraph::new(strings.quit_prompt)
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    let area = centered_rect(width, 3, area);
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn render_note_prompt(f: &mut Frame, strings: &Strings, note: &str, area: Rect) {
+    let popup = Paragraph::new(format!("{}▏", note)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(strings.note_prompt),
+    );
+    let area = centered_rect(60, 3, area);
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+// a rect of at most `width` x `height` cells in the middle of `area`
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+
+    #[test]
+    fn formats_minutes_and_seconds() {
+        assert_eq!(format_mmss(0), "00:00");
+        assert_eq!(format_mmss(59), "00:59");
+        assert_eq!(format_mmss(60), "01:00");
+        assert_eq!(format_mmss(61), "01:01");
+        assert_eq!(format_mmss(1500), "25:00");
+    }
+
+    #[test]
+    fn formats_hours_minutes_and_seconds() {
+        assert_eq!(format_hhmmss(0), "00:00:00");
+        assert_eq!(format_hhmmss(3599), "00:59:59");
+        assert_eq!(format_hhmmss(3600), "01:00:00");
+        assert_eq!(format_hhmmss(36_000 + 61), "10:01:01");
+    }
+
+    #[test]
+    fn reset_logs_work_without_counting_a_pomodoro() {
+        let mut app = App::new(25, 5, 30, 60);
+        app.notes = true;
+        app.interruptions = 2;
+        for _ in 0..90 {
+            app.timer.tick();
+        }
+        app.reset();
+        assert_eq!(app.pomodoros_today, 0);
+        assert_eq!(app.day_streak, 0);
+        assert!(app.work_ends.is_empty());
+        assert!(app.note_input.is_none());
+        assert_eq!(app.interruptions, 0);
+    }
+
+    #[test]
+    fn a_session_is_recorded_without_a_history_log() {
+        let mut app = App::new(1, 5, 30, 60);
+        app.notify = false;
+        app.notes = true;
+        assert!(app.history.is_none());
+        let transition = app.skip();
+        app.on_transition(transition);
+        let (record, _) = app.note_input.as_ref().expect("record held for its note");
+        assert!(record.is_pomodoro());
+    }
+
+    #[test]
+    fn a_paused_phase_is_still_mid_phase() {
+        let mut app = App::new(25, 5, 30, 60);
+        assert!(!app.is_mid_phase());
+        for _ in 0..20 * 60 {
+            app.timer.tick();
+        }
+        app.timer.toggle_pause();
+        assert!(app.is_mid_phase());
+    }
+
+    #[test]
+    fn a_configured_minimum_below_the_default_still_draws() {
+        let mut app = App::new(25, 5, 30, 60);
+        app.min_size = (20, 6);
+        for (width, height) in [(20, 6), (30, 7), (1, 1)] {
+            draw(&app, width, height);
+        }
+        assert!(!text(&draw(&app, 20, 6)).contains("enlarge"));
+        assert!(text(&draw(&app, 20, 5)).contains("enlarge"));
+    }
+
+    #[test]
+    fn held_pause_key_toggles_once() {
+        let start = Instant::now();
+        let mut last = None;
+        assert!(is_debounced(&mut last, start));
+        // key repeats every 50ms for two seconds
+        for i in 1..=40 {
+            assert!(!is_debounced(
+                &mut last,
+                start + Duration::from_millis(50 * i)
+            ));
+        }
+        // let go, then pressed again
+        assert!(is_debounced(&mut last, start + Duration::from_millis(2400)));
+    }
+
+    fn draw(app: &App, width: u16, height: u16) -> ratatui::buffer::Buffer {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|f| ui(f, app)).unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    fn text(buffer: &ratatui::buffer::Buffer) -> String {
+        buffer
+            .content
+            .iter()
+            .map(|cell| cell.symbol.as_str())
+            .collect()
+    }
+
+    // where `needle` starts on screen, searching row by row
+    fn find(buffer: &ratatui::buffer::Buffer, needle: &str) -> Option<(u16, u16)> {
+        let area = buffer.area;
+        (area.top()..area.bottom()).find_map(|y| {
+            let row: Vec<&str> = (area.left()..area.right())
+                .map(|x| buffer.get(x, y).symbol.as_str())
+                .collect();
+            (0..row.len())
+                .find(|&x| row[x..].concat().starts_with(needle))
+                .map(|x| (x as u16, y))
+        })
+    }
+
+    #[test]
+    fn the_gauge_shows_the_countdown_centered() {
+        let mut app = App::new(25, 5, 30, 60);
+        app.sparkline_hours = 0;
+        for _ in 0..75 {
+            app.on_tick();
+        }
+        let buffer = draw(&app, 80, 40);
+        // inside the gauge's border: 78 columns, the 15 of the label centered
+        assert_eq!(find(&buffer, "23:45 remaining"), Some((32, 1)));
+        // and the legend lists every wave, one per row
+        let (x, y) = find(&buffer, "Lunch").unwrap();
+        let at = |y: u16, len: u16| -> String {
+            (x..x + len)
+                .map(|x| buffer.get(x, y).symbol.as_str())
+                .collect()
+        };
+        assert_eq!(at(y - 1, 4), "Work");
+        assert_eq!(at(y - 2, 5), "Break");
+    }
+
+    #[test]
+    fn the_text_view_shows_the_countdown_under_the_phase() {
+        let mut app = App::new(25, 5, 30, 60);
+        app.show_chart = false;
+        let buffer = draw(&app, 30, 20);
+        let (_, y) = find(&buffer, "Work").unwrap();
+        assert_eq!(find(&buffer, "25:00"), Some((13, y + 2)));
+    }
+
+    #[test]
+    fn goal_progress_is_seeded_from_todays_history() {
+        let start = clock::day_start(clock::unix_now());
+        let record = |start, interrupted| history::SessionRecord {
+            phase: "work".to_string(),
+            start,
+            planned_secs: 1500,
+            actual_secs: 1500,
+            wall_secs: 1500,
+            task: None,
+            interruptions: 0,
+            interrupted,
+            abandoned: false,
+            resumed: false,
+            unfinished: false,
+            note: None,
+        };
+        let records = [
+            record(start - 86_400 + 3600, false),
+            record(start, false),
+            record(start + 1, false),
+            record(start + 2, true),
+        ];
+        let mut app = App::new(25, 5, 30, 60);
+        app.goal = 4;
+        app.load_history(&records);
+        assert_eq!(app.pomodoros_today, 2);
+        assert!(app.worked_today);
+        assert_eq!(goal_split(app.pomodoros_today, app.goal), (2, 2));
+    }
+
+    #[test]
+    fn every_wave_starts_at_zero() {
+        let app = App::new(25, 5, 30, 1800);
+        for data in [&app.data1, &app.data2, &app.data3] {
+            assert_eq!(data[0].0, 0.0);
+            assert!(data[0].1.abs() < 1e-9, "{:?}", data[0]);
+        }
+        // and completes one cycle per phase length
+        let mut work = SinSignal::new(1.0, 25.0 * 60.0, 15.0);
+        let quarter = work.nth(375).unwrap();
+        assert!((quarter.1 - 15.0).abs() < 1e-9, "{:?}", quarter);
+    }
+
+    #[test]
+    fn ctrl_c_needs_the_control_modifier() {
+        let key = |c, modifiers| KeyEvent::new(KeyCode::Char(c), modifiers);
+        assert!(is_ctrl_c(&key('c', KeyModifiers::CONTROL)));
+        assert!(is_ctrl_c(&key(
+            'c',
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT
+        )));
+        assert!(!is_ctrl_c(&key('c', KeyModifiers::NONE)));
+        assert!(!is_ctrl_c(&key('d', KeyModifiers::CONTROL)));
+    }
+
+    #[test]
+    fn state_survives_a_window_cycle() {
+        let mut app = App::new(1, 1, 2, 60);
+        app.interruptions = 1;
+        for _ in 0..150 {
+            app.on_tick();
+            while app.window[1] >= 2.0 * app.window_size as f64 {
+                app.cycle_window();
+            }
+        }
+        assert_eq!(app.window, [30.0, 90.0]);
+        assert_eq!(app.timer.completed(), 1);
+        assert_eq!(app.timer.current_phase(), Phase::Work);
+        assert_eq!(app.session_secs, 150);
+        assert_eq!(app.interruptions, 1);
+        for data in [&app.data1, &app.data2, &app.data3] {
+            assert_eq!(data.len(), 60);
+            assert_eq!(data.last().unwrap().0, 89.0);
+        }
+    }
+
+    #[test]
+    fn refuses_to_start_without_a_terminal() {
+        assert!(check_terminal(|| true).is_ok());
+        let err = check_terminal(|| false).unwrap_err();
+        assert!(err.contains("--summary"), "{}", err);
+    }
+
+    #[test]
+    fn waves_stay_accurate_over_a_long_session() {
+        let mut app = App::new(25, 5, 30, 1800);
+        let ticks = 8 * 3600;
+        for _ in 0..ticks {
+            app.on_tick();
+            while app.window[1] >= 2.0 * app.window_size as f64 {
+                app.cycle_window();
+            }
+        }
+        assert!(app.window[0] >= 0.0 && app.window[1] < 3600.0);
+        for (signal, data) in [
+            (&app.signal1, &app.data1),
+            (&app.signal2, &app.data2),
+            (&app.signal3, &app.data3),
+        ] {
+            assert!(signal.x.abs() < 3600.0 && signal.offset < signal.period);
+            // the newest sample is where an unbroken wave would be
+            let n = (app.window_size + ticks - 1) as f64;
+            let expected = (n * 2.0 * std::f64::consts::PI / signal.period).sin() * signal.scale;
+            let (_, y) = *data.last().unwrap();
+            assert!((y - expected).abs() < 1e-6, "{} vs {}", y, expected);
+            // and neighbouring samples never jump
+            let step = 2.0 * std::f64::consts::PI / signal.period * signal.scale;
+            for pair in data.windows(2) {
+                assert_eq!(pair[1].0 - pair[0].0, 1.0);
+                assert!((pair[1].1 - pair[0].1).abs() <= step + 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn x_labels_drop_the_counter_then_the_status_as_the_chart_narrows() {
+        let labels = |width| -> Vec<String> {
+            x_labels(
+                Span::raw("Session 3 of 4"),
+                Span::raw("Work"),
+                Span::raw("12:00"),
+                width,
+            )
+            .iter()
+            .map(|span| span.content.to_string())
+            .collect()
+        };
+        assert_eq!(labels(60), ["Session 3 of 4", "Work", "12:00"]);
+        assert_eq!(labels(30), ["", "Work", "12:00"]);
+        assert_eq!(labels(12), ["", "12:00"]);
+        assert_eq!(labels(0), ["", "12:00"]);
+    }
+
+    #[test]
+    fn y_bounds_fit_the_tallest_wave_with_a_margin() {
+        assert_eq!(y_bounds([3.0, -8.0, 5.0].into_iter()), [-10.0, 10.0]);
+        assert_eq!(y_bounds([0.5].into_iter()), [-2.5, 2.5]);
+        // nothing visible falls back to the fixed range
+        assert_eq!(y_bounds([0.0, 0.0].into_iter()), Y_BOUNDS);
+        assert_eq!(y_bounds(std::iter::empty()), Y_BOUNDS);
+    }
+
+    #[test]
+    fn strict_mode_locks_only_while_a_phase_is_under_way() {
+        let mut app = App::new(1, 1, 1, 60);
+        app.timer.set_pomodoros_per_set(1);
+        assert!(!app.is_locked());
+        app.strict = true;
+        assert!(app.is_locked());
+        app.timer.toggle_pause();
+        assert!(app.is_locked());
+        app.timer.toggle_pause();
+        app.timer.set_manual(true);
+        for _ in 0..60 {
+            app.timer.tick();
+        }
+        assert!(app.timer.is_awaiting());
+        assert!(!app.is_locked());
+        app.timer.start_next();
+        app.timer.set_manual(false);
+        app.timer.set_limit(1);
+        for _ in 0..60 {
+            app.timer.tick();
+        }
+        assert!(app.timer.is_finished());
+        assert!(!app.is_locked());
+    }
+
+    #[test]
+    fn the_tick_fraction_stays_within_one_tick() {
+        let rate = Duration::from_secs(1);
+        assert_eq!(tick_fraction(Duration::ZERO, rate), 0.0);
+        assert_eq!(tick_fraction(Duration::from_millis(250), rate), 0.25);
+        for late in [rate, Duration::from_millis(1500), Duration::from_secs(60)] {
+            let fraction = tick_fraction(late, rate);
+            assert!((0.0..1.0).contains(&fraction), "{}", fraction);
+        }
+    }
+
+    #[test]
+    fn a_custom_window_size_sizes_every_wave() {
+        let mut app = App::new(25, 5, 30, 300);
+        assert_eq!(app.window, [0.0, 300.0]);
+        let lengths = |app: &App| [app.data1.len(), app.data2.len(), app.data3.len()];
+        assert_eq!(lengths(&app), [300; 3]);
+        for _ in 0..700 {
+            app.on_tick();
+            while app.window[1] >= 2.0 * app.window_size as f64 {
+                app.cycle_window();
+            }
+        }
+        assert!(app.window[1] < 600.0);
+        assert_eq!(app.window[1] - app.window[0], 300.0);
+        assert_eq!(lengths(&app), [300; 3]);
+        app.seek(-120);
+        assert_eq!(lengths(&app), [300; 3]);
+    }
+
+    #[test]
+    fn idle_once_the_auto_pause_limit_is_reached() {
+        let limit = Some(Duration::from_secs(300));
+        assert!(!is_idle(Duration::from_secs(299), limit));
+        assert!(is_idle(Duration::from_secs(300), limit));
+        assert!(is_idle(Duration::from_secs(3600), limit));
+        // never idle without --auto-pause-after
+        assert!(!is_idle(Duration::from_secs(3600), None));
+    }
+
+    #[test]
+    fn big_digits_are_five_rows_of_equal_width() {
+        for c in "0123456789".chars() {
+            let glyph = big_glyph(c);
+            assert!(glyph.iter().all(|row| row.chars().count() == 5), "{}", c);
+        }
+        assert_eq!(big_glyph('1')[4], "  ███");
+        assert_eq!(big_glyph(':')[1], " █ ");
+        assert_eq!(big_glyph('x'), ["     "; 5]);
+        // "12:34" is four digits, a colon and a space between each glyph
+        let rows = big_text("12:34");
+        assert_eq!(rows.len(), 5);
+        assert!(rows.iter().all(|row| row.chars().count() == 4 * 5 + 3 + 4));
+    }
+
+    #[test]
+    fn zen_text_scales_and_centers_in_the_terminal() {
+        let area = Rect::new(0, 0, 80, 24);
+        assert_eq!(zen_scale(27, 5, area), 2);
+        assert_eq!(zen_scale(27, 5, Rect::new(0, 0, 20, 4)), 1);
+        assert_eq!(zen_scale(0, 5, area), 1);
+        assert_eq!(centered_rect(54, 10, area), Rect::new(13, 7, 54, 10));
+        assert_eq!(centered_rect(100, 30, area), area);
+        let offset = Rect::new(10, 5, 20, 10);
+        assert_eq!(centered_rect(10, 4, offset), Rect::new(15, 8, 10, 4));
+    }
+
+    #[test]
+    fn the_title_only_changes_with_the_minute_phase_or_pause() {
+        let mut app = App::new(25, 5, 30, 60);
+        let start = app.title_key();
+        app.timer.tick();
+        // 24:59 still reads as the 25th minute
+        assert_eq!(app.title_key(), start);
+        for _ in 0..58 {
+            app.timer.tick();
+        }
+        assert_eq!(app.title_key(), start);
+        app.timer.tick();
+        let next_minute = app.title_key();
+        assert_ne!(next_minute, start);
+        app.timer.toggle_pause();
+        assert_ne!(app.title_key(), next_minute);
+        app.timer.toggle_pause();
+        assert_eq!(app.title_key(), next_minute);
+        app.timer.skip();
+        assert_ne!(app.title_key().0, next_minute.0);
+    }
+
+    #[test]
+    fn the_history_view_selection_stops_at_both_ends() {
+        let record = |start| history::SessionRecord {
+            phase: "work".to_string(),
+            start,
+            planned_secs: 1500,
+            actual_secs: 1500,
+            wall_secs: 1500,
+            task: None,
+            interruptions: 0,
+            interrupted: false,
+            abandoned: false,
+            resumed: false,
+            unfinished: false,
+            note: None,
+        };
+        let mut view = HistoryView {
+            records: (0..3).map(record).collect(),
+            selected: 0,
+        };
+        view.scroll(-1);
+        assert_eq!(view.selected, 0);
+        view.scroll(1);
+        assert_eq!(view.selected, 1);
+        view.scroll(10);
+        assert_eq!(view.selected, 2);
+        view.scroll(1);
+        assert_eq!(view.selected, 2);
+        view.scroll(-10);
+        assert_eq!(view.selected, 0);
+        let mut empty = HistoryView {
+            records: Vec::new(),
+            selected: 0,
+        };
+        empty.scroll(1);
+        assert_eq!(empty.selected, 0);
+    }
+
+    #[test]
+    fn an_unwritable_history_is_disabled_once() {
+        // a regular file where the log's directory should be
+        let blocker = std::env::temp_dir().join(format!("pomodoro-blocker-{}", process::id()));
+        fs::write(&blocker, "").unwrap();
+        let path = blocker.join("history.jsonl");
+        let err = history::check_writable(&path).unwrap_err();
+        let mut app = App::new(1, 5, 30, 60);
+        app.notify = false;
+        app.history = Some(path);
+        app.disable_history(err);
+        assert!(app.history.is_none());
+        assert_eq!(app.errors.len(), 1);
+        assert!(app.toast.is_some());
+        // sessions still count, without trying the log again
+        let transition = app.skip();
+        app.on_transition(transition);
+        assert_eq!(app.pomodoros_today, 1);
+        assert_eq!(app.errors.len(), 1);
+        fs::remove_file(&blocker).unwrap();
+    }
+
+    #[test]
+    fn stopping_the_stopwatch_logs_its_time() {
+        let path = std::env::temp_dir().join(format!("pomodoro-stopwatch-{}.jsonl", process::id()));
+        let _ = fs::remove_file(&path);
+        let mut app = App::new(25, 5, 30, 60);
+        app.notify = false;
+        app.history = Some(path.clone());
+        app.timer.set_stopwatch(true);
+        for _ in 0..2000 {
+            app.on_tick();
+        }
+        let transition = app.skip();
+        app.on_transition(transition);
+        let records = history::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].phase, "work");
+        assert_eq!(records[0].actual_secs, 2000);
+        assert_eq!(records[0].planned_secs, 2000);
+        assert_eq!(app.timer.clock_secs(), 0);
+    }
+
+    #[test]
+    fn a_fixed_seed_picks_the_same_activities() {
+        const NAMES: [&str; 3] = ["pushups", "stretch", "water"];
+        let activities = |seed| {
+            let mut app = App::new(25, 5, 30, 60);
+            app.micro_activities = NAMES.map(String::from).to_vec();
+            app.rng = rng::Rng::new(seed);
+            (0..8)
+                .map(|_| {
+                    app.next_tip();
+                    app.activity.map(|i| NAMES[i])
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            activities(7),
+            [
+                Some("stretch"),
+                None,
+                Some("pushups"),
+                Some("pushups"),
+                Some("pushups"),
+                Some("water"),
+                None,
+                Some("stretch"),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_tick_that_does_nothing_leaves_the_screen_clean() {
+        let mut app = App::new(25, 5, 30, 60);
+        app.dirty = false;
+        app.timer.toggle_pause();
+        assert!(app.on_tick().is_none());
+        assert!(!app.dirty);
+        app.expire_toast();
+        app.update_clock();
+        assert!(!app.dirty);
+        app.timer.toggle_pause();
+        app.on_tick();
+        assert!(app.dirty);
+    }
+
+    #[test]
+    fn an_unfinished_session_carries_its_task_into_the_next() {
+        let mut app = App::new(25, 5, 30, 60);
+        app.notify = false;
+        app.begun = true;
+        app.task = "docs".to_string();
+        app.toggle_unfinished();
+        let transition = app.skip();
+        app.on_transition(transition);
+        assert!(app.carried_over);
+        assert!(!app.unfinished);
+        let transition = app.skip();
+        app.on_transition(transition);
+        assert_eq!(app.timer.current_phase(), Phase::Work);
+        assert_eq!(app.task, "docs");
+        // once the toast from marking it has gone
+        app.toast = None;
+        assert!(text(&draw(&app, 80, 40)).contains("↻ docs"));
+        // only the one session carries over unless marked again
+        let transition = app.skip();
+        app.on_transition(transition);
+        assert!(!app.carried_over);
+    }
+
+    #[test]
+    fn colorblind_mode_gives_each_wave_its_own_marker() {
+        let mut app = App::new(25, 5, 30, 60);
+        let markers = |app: &App| Phase::ALL.map(|phase| app.wave_marker(phase));
+        assert_eq!(markers(&app), [app.marker; 3]);
+        app.wave_markers = Some(COLORBLIND_MARKERS);
+        let [a, b, c] = markers(&app);
+        assert!(a != b && b != c && a != c);
+    }
+
+    #[test]
+    fn only_key_presses_toggle() {
+        let key = |code, kind| KeyEvent::new_with_kind(code, KeyModifiers::NONE, kind);
+        for code in [KeyCode::Char(' '), KeyCode::Char('q'), KeyCode::Char('s')] {
+            assert!(is_actionable(&key(code, KeyEventKind::Press), false));
+            assert!(!is_actionable(&key(code, KeyEventKind::Repeat), false));
+            assert!(!is_actionable(&key(code, KeyEventKind::Release), false));
+        }
+        // seeking keeps going while an arrow is held
+        assert!(is_actionable(
+            &key(KeyCode::Left, KeyEventKind::Repeat),
+            false
+        ));
+        assert!(!is_actionable(
+            &key(KeyCode::Left, KeyEventKind::Release),
+            false
+        ));
+        // and so does typing, but a release still types nothing
+        let c = KeyCode::Char('q');
+        assert!(is_actionable(&key(c, KeyEventKind::Press), true));
+        assert!(is_actionable(&key(c, KeyEventKind::Repeat), true));
+        assert!(!is_actionable(&key(c, KeyEventKind::Release), true));
+    }
+
+    #[test]
+    fn quitting_mid_work_logs_an_interrupted_session() {
+        let path = std::env::temp_dir().join(format!("pomodoro-quit-{}.jsonl", process::id()));
+        let _ = fs::remove_file(&path);
+        let mut app = App::new(25, 5, 30, 60);
+        app.history = Some(path.clone());
+        for _ in 0..600 {
+            app.on_tick();
+        }
+        app.on_quit();
+        let records = history::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(records.len(), 1);
+        assert!(records[0].interrupted);
+        assert!(!records[0].is_pomodoro());
+        assert_eq!(records[0].actual_secs, 600);
+        assert_eq!(records[0].planned_secs, 1500);
+        assert_eq!(app.pomodoros_today, 0);
+    }
+
+    #[test]
+    fn quitting_before_anything_ran_logs_nothing() {
+        let path =
+            std::env::temp_dir().join(format!("pomodoro-quit-early-{}.jsonl", process::id()));
+        let _ = fs::remove_file(&path);
+        let mut app = App::new(25, 5, 30, 60);
+        app.history = Some(path.clone());
+        app.on_quit();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn clicks_map_to_the_legend_the_chart_or_nothing() {
+        let app = App::new(25, 5, 30, 60);
+        let size = Rect::new(0, 0, 80, 40);
+        let buffer = draw(&app, size.width, size.height);
+        let click = |(x, y)| click_target(&app, size, x, y);
+        // the legend lists Break, Work and Lunch from the top
+        let (x, y) = find(&buffer, "Lunch").unwrap();
+        assert_eq!(click((x, y - 2)), Some(Click::Wave(0)));
+        assert_eq!(click((x + 2, y - 1)), Some(Click::Wave(1)));
+        assert_eq!(click((x, y)), Some(Click::Wave(2)));
+        // anywhere else on the chart pauses
+        assert_eq!(click((x - 20, y)), Some(Click::Pause));
+        assert_eq!(click((1, 30)), Some(Click::Pause));
+        // the gauge above isn't part of it, nor is anything off screen
+        assert_eq!(click((40, 1)), None);
+        assert_eq!(click((80, 45)), None);
+        // and a screen too small to draw has nothing to click
+        assert_eq!(click_target(&app, Rect::new(0, 0, 10, 4), 1, 1), None);
+    }
+
+    #[test]
+    fn the_daily_tally_rolls_over_at_midnight() {
+        let path = std::env::temp_dir().join(format!("pomodoro-midnight-{}.jsonl", process::id()));
+        let _ = fs::remove_file(&path);
+        let midnight = clock::day_start(clock::unix_now());
+        let record = |start, actual_secs| history::SessionRecord {
+            phase: "work".to_string(),
+            start,
+            planned_secs: 1500,
+            actual_secs,
+            wall_secs: actual_secs,
+            task: None,
+            interruptions: 0,
+            interrupted: false,
+            abandoned: false,
+            resumed: false,
+            unfinished: false,
+            note: None,
+        };
+        for record in [
+            record(midnight - 7200, 1500),
+            record(midnight - 3600, 1500),
+            record(midnight, 1200),
+        ] {
+            history::append(&path, &record).unwrap();
+        }
+        let mut app = App::new(25, 5, 30, 60);
+        app.history = Some(path.clone());
+        // where yesterday's run had got to before midnight
+        app.today = clock::local(midnight - 1).date;
+        app.pomodoros_today = 2;
+        app.focus_today = 3000;
+        app.focus_line.push((10.0, 50.0));
+        app.on_tick();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(app.today, clock::today());
+        assert_eq!(app.pomodoros_today, 1);
+        assert_eq!(app.focus_today, 1200);
+        assert_eq!(app.focus_today_secs(), 1201);
+        assert_eq!(app.day_streak, 2);
+        assert!(app.focus_line.is_empty());
+    }
+
+    #[cfg(feature = "sound")]
+    #[test]
+    fn an_unplayable_sound_falls_back_to_the_bell() {
+        let mut app = App::new(25, 5, 30, 60);
+        app.bell = false;
+        app.sound = Some(PathBuf::from("/nonexistent/pomodoro-ding.wav"));
+        app.ring(Phase::Work);
+        app.ring(Phase::Work);
+        assert_eq!(app.errors.len(), 1);
+        assert!(app.errors[0].contains("using the terminal bell"));
+    }
+
+    #[test]
+    fn at_waits_only_for_a_time_still_to_come_today() {
+        let now = clock::LocalTime {
+            date: clock::today(),
+            hour: 13,
+            minute: 59,
+            second: 30,
+        };
+        let at = clock::TimeOfDay::new;
+        assert_eq!(start_delay(&now, at(14, 0)), Some(30));
+        assert_eq!(start_delay(&now, at(23, 30)), Some(9 * 3600 + 30 * 60 + 30));
+        assert_eq!(start_delay(&now, at(13, 59)), None);
+        assert_eq!(start_delay(&now, at(9, 0)), None);
+        assert_eq!(start_delay(&now, at(0, 0)), None);
+    }
+
+    thread_local! {
+        static BEEPS: std::cell::Cell<u32> = const { std::cell::Cell::new(0) };
+    }
+
+    fn count_beep(_: Phase) {
+        BEEPS.with(|beeps| beeps.set(beeps.get() + 1));
+    }
+
+    #[test]
+    fn the_bell_rings_once_per_transition() {
+        let mut app = App::new(1, 1, 2, 60);
+        app.notify = false;
+        app.bell = true;
+        app.beep = count_beep;
+        let mut transitions = 0;
+        for _ in 0..120 {
+            if let Some(transition) = app.on_tick() {
+                app.on_transition(transition);
+                transitions += 1;
+                assert_eq!(BEEPS.with(|beeps| beeps.get()), transitions);
+            }
+        }
+        assert_eq!(transitions, 2);
+        // several phases caught up at once still ring only the once
+        app.catch_up(180);
+        assert_eq!(BEEPS.with(|beeps| beeps.get()), 3);
+        app.bell = false;
+        let transition = app.timer.skip();
+        app.on_transition(transition);
+        assert_eq!(BEEPS.with(|beeps| beeps.get()), 3);
+    }
+
+    #[test]
+    fn interruptions_reset_at_each_phase_boundary() {
+        let mut app = App::new(1, 1, 2, 60);
+        app.notify = false;
+        app.notes = true;
+        app.interruptions = 2;
+        let transition = app.skip();
+        app.on_transition(transition);
+        assert_eq!(app.timer.current_phase(), Phase::ShortBreak);
+        assert_eq!(app.interruptions, 0);
+        let (record, _) = app.note_input.take().unwrap();
+        assert_eq!(record.interruptions, 2);
+        let transition = app.skip();
+        app.on_transition(transition);
+        app.interruptions = 1;
+        let transition = (0..120).find_map(|_| app.on_tick()).unwrap();
+        app.on_transition(transition);
+        assert_eq!(app.timer.current_phase(), Phase::ShortBreak);
+        assert_eq!(app.interruptions, 0);
+        let (record, _) = app.note_input.take().unwrap();
+        assert_eq!(record.interruptions, 1);
+    }
 }
@@ -0,0 +1,186 @@
+use crate::timer::Phase;
+use std::{
+    io::{self, Write},
+    process::{Command, Stdio},
+    thread,
+    time::Duration,
+};
+
+// Fire-and-forget: a missing notification daemon must never take the TUI down.
+pub fn send(summary: &str, body: &str) {
+    let mut command = match platform_command(summary, body) {
+        Some(command) => command,
+        None => return,
+    };
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    thread::spawn(move || {
+        let _ = command.status();
+    });
+}
+
+// Replace each `{name}` in a configured message with its value; names that
+// aren't in `values` and unclosed braces are left as written.
+pub fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let value = after.find('}').and_then(|close| {
+            let name = &after[..close];
+            let (_, value) = values.iter().find(|(key, _)| *key == name)?;
+            Some((value, close))
+        });
+        match value {
+            Some((value, close)) => {
+                out.push_str(value);
+                rest = &after[close + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(target_os = "macos")]
+fn platform_command(summary: &str, body: &str) -> Option<Command> {
+    let script = format!(
+        "display notification {} with title {}",
+        applescript_string(body),
+        applescript_string(summary)
+    );
+    let mut command = Command::new("osascript");
+    command.arg("-e").arg(script);
+    Some(command)
+}
+
+// An AppleScript string literal; `{:?}` would write Rust escapes such as the
+// `\u{200d}` inside many emoji, which AppleScript would show as typed.
+#[cfg(any(target_os = "macos", test))]
+fn applescript_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        if c == '\\' || c == '"' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn platform_command(summary: &str, body: &str) -> Option<Command> {
+    let mut command = Command::new("notify-send");
+    command.arg("--app-name=pomodoro").arg(summary).arg(body);
+    Some(command)
+}
+
+#[cfg(not(unix))]
+fn platform_command(_summary: &str, _body: &str) -> Option<Command> {
+    None
+}
+
+// BEL leaves the screen contents and terminal modes alone, so it is safe to
+// emit while the alternate screen is active.
+pub fn bell<W: Write>(out: &mut W) -> io::Result<()> {
+    out.write_all(b"\x07")?;
+    out.flush()
+}
+
+// Milliseconds to wait before each BEL, so the end of each phase can be told
+// apart by ear: one beep after work, two after a short break, and a quick run
+// of beeps, which most terminals blur into one long tone, after a long break.
+pub fn beep_pattern(phase: Phase) -> &'static [u64] {
+    match phase {
+        Phase::Work => &[0],
+        Phase::ShortBreak => &[0, 250],
+        Phase::LongBreak => &[0, 60, 60, 60, 60, 60],
+    }
+}
+
+// Play the pattern for the phase that just ended on its own thread so the
+// sleeps never hold up rendering.
+pub fn beep(phase: Phase) {
+    thread::spawn(move || {
+        for &gap in beep_pattern(phase) {
+            thread::sleep(Duration::from_millis(gap));
+            if bell(&mut io::stdout()).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bell_writes_a_single_bel() {
+        let mut out = Vec::new();
+        bell(&mut out).unwrap();
+        assert_eq!(out, b"\x07");
+    }
+
+    #[test]
+    fn fills_each_placeholder() {
+        let values = [
+            ("completed", "3"),
+            ("task", "docs"),
+            ("next_phase", "Short break"),
+            ("duration", "25:00"),
+        ];
+        assert_eq!(fill("{completed} done", &values), "3 done");
+        assert_eq!(fill("on {task}", &values), "on docs");
+        assert_eq!(fill("{next_phase} next", &values), "Short break next");
+        assert_eq!(fill("took {duration}", &values), "took 25:00");
+        assert_eq!(
+            fill("{task}: {completed} in {duration}", &values),
+            "docs: 3 in 25:00"
+        );
+    }
+
+    #[test]
+    fn leaves_unknown_names_and_stray_braces_alone() {
+        let values = [("task", "docs")];
+        assert_eq!(fill("{who} on {task}", &values), "{who} on docs");
+        assert_eq!(fill("{task", &values), "{task");
+        assert_eq!(fill("{{task}}", &values), "{docs}");
+        assert_eq!(fill("", &values), "");
+    }
+
+    #[test]
+    fn each_phase_ending_rings_its_own_pattern() {
+        assert_eq!(beep_pattern(Phase::Work), [0]);
+        assert_eq!(beep_pattern(Phase::ShortBreak), [0, 250]);
+        let long = beep_pattern(Phase::LongBreak);
+        assert_eq!(long.len(), 6);
+        assert!(long[1..].iter().all(|&gap| gap < 250));
+        // every pattern starts straight away
+        for phase in Phase::ALL {
+            assert_eq!(beep_pattern(phase)[0], 0);
+        }
+    }
+
+    #[test]
+    fn quotes_text_for_applescript() {
+        assert_eq!(applescript_string("Break over"), "\"Break over\"");
+        assert_eq!(
+            applescript_string(r#"say "hi" \ bye"#),
+            r#""say \"hi\" \\ bye""#
+        );
+        assert_eq!(
+            applescript_string("Done 👩\u{200d}💻\tcafé"),
+            "\"Done 👩\u{200d}💻\tcafé\""
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{json::Value, timer::Phase};
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+// a client that connects and says nothing shouldn't hold up the next one
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+// What GET /status reports; the UI thread overwrites it every loop.
+#[derive(Clone)]
+pub struct Status {
+    pub phase: Phase,
+    pub label: String,
+    pub remaining_secs: u64,
+    pub completed: u32,
+    pub paused: bool,
+}
+
+impl Status {
+    pub fn to_json(&self) -> Value {
+        Value::Object(vec![
+            (
+                "phase".to_string(),
+                Value::String(self.phase.key().to_string()),
+            ),
+            ("label".to_string(), Value::String(self.label.clone())),
+            (
+                "remaining_secs".to_string(),
+                Value::Number(self.remaining_secs as f64),
+            ),
+            (
+                "completed".to_string(),
+                Value::Number(f64::from(self.completed)),
+            ),
+            ("paused".to_string(), Value::Bool(self.paused)),
+        ])
+    }
+
+    pub fn from_json(value: &Value) -> Option<Status> {
+        Some(Status {
+            phase: Phase::from_key(value.get("phase")?.as_str()?)?,
+            label: value.get("label")?.as_str()?.to_string(),
+            remaining_secs: value.get("remaining_secs")?.as_u64()?,
+            completed: value.get("completed")?.as_u64()? as u32,
+            paused: matches!(value.get("paused"), Some(Value::Bool(true))),
+        })
+    }
+}
+
+pub struct Server {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+impl Server {
+    pub fn start(addr: SocketAddr, status: Arc<Mutex<Status>>) -> io::Result<Server> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        if let Err(err) = respond(stream, &status) {
+                            log::debug!("status request failed: {}", err);
+                        }
+                    }
+                }
+            })
+        };
+        log::info!("serving status on http://{}/status", addr);
+        Ok(Server { addr, stop, handle })
+    }
+
+    // accept() blocks, so wake it with a connection of our own after setting the flag
+    pub fn shutdown(self) {
+        self.stop.store(true, Ordering::SeqCst);
+        let _ = TcpStream::connect(self.addr);
+        let _ = self.handle.join();
+    }
+}
+
+fn respond(mut stream: TcpStream, status: &Mutex<Status>) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut request = String::new();
+    BufReader::new(&stream).read_line(&mut request)?;
+    let mut parts = request.split_whitespace();
+    let (code, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/status")) => {
+            let status = status.lock().unwrap_or_else(|e| e.into_inner()).clone();
+            ("200 OK", status.to_json().to_string())
+        }
+        (Some("GET"), _) => ("404 Not Found", r#"{"error":"not found"}"#.to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            r#"{"error":"method not allowed"}"#.to_string(),
+        ),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        code,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
@@ -0,0 +1,58 @@
+use std::{
+    io,
+    path::Path,
+    process::{Command, Stdio},
+    thread,
+};
+
+// Hand the file to the platform's command-line player. Spawning doesn't wait
+// for playback, and the child is reaped on its own thread, so the render loop
+// never stalls; a missing file or player comes back as an error instead.
+// Shelling out keeps an audio decoder and the ALSA headers out of the build.
+pub fn play(path: &Path) -> io::Result<()> {
+    if !path.is_file() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "no such file"));
+    }
+    let mut child = player(path)?
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn player(path: &Path) -> io::Result<Command> {
+    let mut command = Command::new("afplay");
+    command.arg(path);
+    Ok(command)
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn player(path: &Path) -> io::Result<Command> {
+    let mut command = Command::new("aplay");
+    command.arg("-q").arg(path);
+    Ok(command)
+}
+
+#[cfg(not(unix))]
+fn player(_path: &Path) -> io::Result<Command> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "no sound player on this platform",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_missing_file_is_not_found() {
+        let err = play(Path::new("/nonexistent/pomodoro-ding.wav")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,110 @@
+// The running TUI mirrors its status to a small file so `--print-once` can
+// show it in a tmux or polybar status line.
+
+use crate::{
+    json::{self, Value},
+    serve::Status,
+};
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+// The TUI rewrites the file every second, even while paused, so anything
+// older than this was left behind by an instance that's gone.
+const STALE_SECS: u64 = 5;
+
+pub fn default_path() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_STATE_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?)
+            .join(".local")
+            .join("state"),
+    };
+    Some(base.join("pomodoro").join("state.json"))
+}
+
+pub fn to_json(status: &Status, now: u64) -> Value {
+    Value::Object(vec![
+        ("updated".to_string(), Value::Number(now as f64)),
+        ("status".to_string(), status.to_json()),
+    ])
+}
+
+// None when the file's stale or doesn't hold a status
+pub fn from_json(value: &Value, now: u64) -> Option<Status> {
+    let updated = value.get("updated")?.as_u64()?;
+    if now.saturating_sub(updated) > STALE_SECS {
+        return None;
+    }
+    Status::from_json(value.get("status")?)
+}
+
+// Written beside the target and renamed over it so a reader never sees half a file.
+pub fn write(path: &Path, status: &Status, now: u64) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, format!("{}\n", to_json(status, now)))?;
+    fs::rename(&tmp, path)
+}
+
+pub fn read(path: &Path, now: u64) -> Option<Status> {
+    let text = fs::read_to_string(path).ok()?;
+    from_json(&json::parse(text.trim()).ok()?, now)
+}
+
+pub fn remove(path: &Path) {
+    let _ = fs::remove_file(path);
+}
+
+// "🍅 Work 12:34", or "idle" with no instance running
+pub fn line(status: Option<&Status>) -> String {
+    let Some(status) = status else {
+        return "idle".to_string();
+    };
+    format!(
+        "{} {} {:02}:{:02}",
+        if status.paused { "⏸" } else { "🍅" },
+        status.label,
+        status.remaining_secs / 60,
+        status.remaining_secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::Phase;
+
+    fn status() -> Status {
+        Status {
+            phase: Phase::Work,
+            label: "Work".to_string(),
+            remaining_secs: 754,
+            completed: 3,
+            paused: false,
+        }
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let text = to_json(&status(), 1000).to_string();
+        let read = from_json(&json::parse(&text).unwrap(), 1002).unwrap();
+        assert_eq!(read.phase, Phase::Work);
+        assert_eq!(read.label, "Work");
+        assert_eq!(read.remaining_secs, 754);
+        assert_eq!(read.completed, 3);
+        assert!(!read.paused);
+        assert_eq!(line(Some(&read)), "🍅 Work 12:34");
+    }
+
+    #[test]
+    fn a_stale_file_reads_as_idle() {
+        let value = to_json(&status(), 1000);
+        assert!(from_json(&value, 1000 + STALE_SECS).is_some());
+        assert!(from_json(&value, 1001 + STALE_SECS).is_none());
+        assert_eq!(line(None), "idle");
+    }
+}
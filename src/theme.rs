@@ -0,0 +1,90 @@
+use crate::Phase;
+use ratatui::style::Color;
+
+pub const NAMES: &[&str] = &["dark", "light", "solarized", "colorblind"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    pub work: Color,
+    pub short_break: Color,
+    pub long_break: Color,
+    pub axis: Color,
+    pub label: Color,
+}
+
+impl Palette {
+    pub fn named(name: &str) -> Option<Palette> {
+        match name {
+            "dark" => Some(Palette {
+                work: Color::Red,
+                short_break: Color::Cyan,
+                long_break: Color::Yellow,
+                axis: Color::Gray,
+                label: Color::White,
+            }),
+            "light" => Some(Palette {
+                work: Color::Red,
+                short_break: Color::Blue,
+                long_break: Color::Magenta,
+                axis: Color::DarkGray,
+                label: Color::Black,
+            }),
+            "solarized" => Some(Palette {
+                work: Color::Rgb(220, 50, 47),
+                short_break: Color::Rgb(42, 161, 152),
+                long_break: Color::Rgb(181, 137, 0),
+                axis: Color::Rgb(88, 110, 117),
+                label: Color::Rgb(147, 161, 161),
+            }),
+            // Okabe-Ito vermillion, blue and yellow, apart for every common
+            // kind of color blindness
+            "colorblind" => Some(Palette {
+                work: Color::Rgb(213, 94, 0),
+                short_break: Color::Rgb(0, 114, 178),
+                long_break: Color::Rgb(240, 228, 66),
+                axis: Color::Gray,
+                label: Color::White,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn phase(&self, phase: Phase) -> Color {
+        match phase {
+            Phase::Work => self.work,
+            Phase::ShortBreak => self.short_break,
+            Phase::LongBreak => self.long_break,
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Palette {
+        Palette::named("dark").unwrap()
+    }
+}
+
+// Mix `from` into `to` by how much of the phase is left: all of it gives
+// `from`, none gives `to`.
+pub fn blend(from: (u8, u8, u8), to: (u8, u8, u8), remaining: f64) -> Color {
+    let remaining = remaining.clamp(0.0, 1.0);
+    let mix =
+        |a: u8, b: u8| (f64::from(b) + (f64::from(a) - f64::from(b)) * remaining).round() as u8;
+    Color::Rgb(mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blends_from_the_start_color_to_the_end_color() {
+        let (green, red) = ((0, 200, 0), (200, 0, 100));
+        assert_eq!(blend(green, red, 1.0), Color::Rgb(0, 200, 0));
+        assert_eq!(blend(green, red, 0.5), Color::Rgb(100, 100, 50));
+        assert_eq!(blend(green, red, 0.0), Color::Rgb(200, 0, 100));
+        // out of range sticks to the ends
+        assert_eq!(blend(green, red, 1.5), Color::Rgb(0, 200, 0));
+        assert_eq!(blend(green, red, -0.5), Color::Rgb(200, 0, 100));
+    }
+}
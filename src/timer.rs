@@ -0,0 +1,1002 @@
+use crate::clock;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+impl Phase {
+    // same order as the chart datasets
+    pub const ALL: [Phase; 3] = [Phase::ShortBreak, Phase::Work, Phase::LongBreak];
+
+    // position in `ALL`
+    pub fn index(self) -> usize {
+        match self {
+            Phase::ShortBreak => 0,
+            Phase::Work => 1,
+            Phase::LongBreak => 2,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Phase::Work => "Work",
+            Phase::ShortBreak => "Break",
+            Phase::LongBreak => "Lunch",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Phase> {
+        Phase::ALL.into_iter().find(|phase| phase.key() == key)
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Phase::Work => "work",
+            Phase::ShortBreak => "short_break",
+            Phase::LongBreak => "long_break",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Transition {
+    pub from: Phase,
+    pub to: Phase,
+    pub started: u64,
+    pub elapsed: u64,
+    // the length `from` was meant to run, after any '+' or '-'
+    pub planned: u64,
+    // wall-clock seconds `from` spent paused, on top of `elapsed`
+    pub paused_secs: u64,
+    // `from` was a one-off deep work phase rather than a scheduled one
+    pub deep_work: bool,
+}
+
+// Phase lengths in minutes and the set size, as one of the config's
+// [schedules] or the top-level settings.
+#[derive(Clone)]
+pub struct Schedule {
+    pub name: String,
+    pub work: u64,
+    pub short_break: u64,
+    pub long_break: u64,
+    pub pomodoros_per_set: u32,
+}
+
+// One phase of a --plan, which runs its steps in order and then loops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub phase: Phase,
+    pub minutes: u64,
+}
+
+// where the schedule stood when a deep work phase pushed in
+struct Suspended {
+    phase: Phase,
+    elapsed: u64,
+    adjust: i64,
+    paused_for: Duration,
+}
+
+// The phase clock on its own: one `tick` is one second of the schedule, and
+// nothing here knows about the terminal or the chart.
+pub struct Timer {
+    paused: bool,
+    // time this phase has spent paused: finished pauses, and when the
+    // current one began
+    paused_for: Duration,
+    paused_at: Option<Instant>,
+    // in manual mode each phase waits for `start_next` before it runs
+    manual: bool,
+    awaiting: bool,
+    // --stopwatch: one open-ended work phase counting up until `skip`
+    stopwatch: bool,
+    phase: Phase,
+    phase_started: u64,
+    elapsed: u64,
+    // seconds added to (or taken off) this phase only, from '+' and '-'
+    adjust: i64,
+    // set while a deep work phase runs in place of the scheduled one
+    deep_work: Option<Suspended>,
+    completed: u32,
+    // sets whose long break has also run out
+    sets_finished: u32,
+    // stop for good after this many sets; 0 runs forever
+    set_limit: u32,
+    pomodoros_per_set: u32,
+    // planned length of a whole set, cached since it only changes with the schedule
+    set_secs: u64,
+    // phase lengths in minutes
+    work: u64,
+    short_break: u64,
+    long_break: u64,
+    // --plan: these steps in place of the schedule, and the one under way
+    plan: Vec<Step>,
+    step: usize,
+}
+
+impl Timer {
+    pub fn new(work: u64, short_break: u64, long_break: u64, pomodoros_per_set: u32) -> Timer {
+        let mut timer = Timer {
+            paused: false,
+            paused_for: Duration::ZERO,
+            paused_at: None,
+            manual: false,
+            awaiting: false,
+            stopwatch: false,
+            phase: Phase::Work,
+            phase_started: clock::unix_now(),
+            elapsed: 0,
+            adjust: 0,
+            deep_work: None,
+            completed: 0,
+            sets_finished: 0,
+            set_limit: 0,
+            pomodoros_per_set,
+            set_secs: 0,
+            work,
+            short_break,
+            long_break,
+            plan: Vec::new(),
+            step: 0,
+        };
+        timer.set_pomodoros_per_set(pomodoros_per_set);
+        timer
+    }
+
+    pub fn set_pomodoros_per_set(&mut self, pomodoros_per_set: u32) {
+        self.pomodoros_per_set = pomodoros_per_set;
+        let n = u64::from(pomodoros_per_set);
+        self.set_secs = n * self.phase_secs(Phase::Work)
+            + (n - 1) * self.phase_secs(Phase::ShortBreak)
+            + self.phase_secs(Phase::LongBreak);
+    }
+
+    // Walk `plan` from its first step instead of the schedule; a set is one
+    // pass through it. Each phase's usual length becomes that of its first
+    // step, for anything that asks without a step in mind.
+    pub fn set_plan(&mut self, plan: Vec<Step>) {
+        for phase in Phase::ALL {
+            if let Some(step) = plan.iter().find(|step| step.phase == phase) {
+                match phase {
+                    Phase::Work => self.work = step.minutes,
+                    Phase::ShortBreak => self.short_break = step.minutes,
+                    Phase::LongBreak => self.long_break = step.minutes,
+                }
+            }
+        }
+        let works = plan.iter().filter(|step| step.phase == Phase::Work).count();
+        self.pomodoros_per_set = (works as u32).max(1);
+        self.set_secs = plan.iter().map(|step| step.minutes * 60).sum();
+        self.plan = plan;
+        self.reset();
+    }
+
+    // Switch lengths, restarting the current phase; finished pomodoros still count.
+    pub fn set_schedule(&mut self, schedule: &Schedule) {
+        self.work = schedule.work;
+        self.short_break = schedule.short_break;
+        self.long_break = schedule.long_break;
+        self.set_pomodoros_per_set(schedule.pomodoros_per_set);
+        if let Some(suspended) = self.deep_work.take() {
+            self.phase = suspended.phase;
+        }
+        self.restart_pause_count();
+        self.phase_started = clock::unix_now();
+        self.elapsed = 0;
+        self.adjust = 0;
+    }
+
+    pub fn current_phase(&self) -> Phase {
+        self.phase
+    }
+
+    pub fn phase_started(&self) -> u64 {
+        self.phase_started
+    }
+
+    pub fn elapsed(&self) -> u64 {
+        self.elapsed
+    }
+
+    pub fn completed(&self) -> u32 {
+        self.completed
+    }
+
+    pub fn sets_completed(&self) -> u32 {
+        self.completed / self.pomodoros_per_set
+    }
+
+    pub fn sets_finished(&self) -> u32 {
+        self.sets_finished
+    }
+
+    pub fn set_limit(&mut self, sets: u32) {
+        self.set_limit = sets;
+    }
+
+    // every set asked for is done; nothing ticks from here on
+    pub fn is_finished(&self) -> bool {
+        self.set_limit > 0 && self.sets_finished >= self.set_limit
+    }
+
+    // Pomodoros left to finish, counting the current one, before the long
+    // break; 1 means it comes next. None during the long break itself.
+    pub fn until_long_break(&self) -> Option<u32> {
+        if self.stopwatch {
+            return None;
+        }
+        if !self.plan.is_empty() {
+            return self.plan_until_long_break();
+        }
+        match self.scheduled_phase() {
+            Phase::LongBreak => None,
+            Phase::Work | Phase::ShortBreak => {
+                Some(self.pomodoros_per_set - self.completed % self.pomodoros_per_set)
+            }
+        }
+    }
+
+    // work steps from here to the plan's next long break, if it has one
+    fn plan_until_long_break(&self) -> Option<u32> {
+        let long_break = |step: &Step| step.phase == Phase::LongBreak;
+        if long_break(&self.plan[self.step]) || !self.plan.iter().any(long_break) {
+            return None;
+        }
+        let ahead = self.plan[self.step..].iter().chain(&self.plan[..self.step]);
+        let works = ahead
+            .take_while(|step| !long_break(step))
+            .filter(|step| step.phase == Phase::Work)
+            .count();
+        // a break straight before the long one has no work left to count
+        Some((works as u32).max(1))
+    }
+
+    pub fn is_deep_work(&self) -> bool {
+        self.deep_work.is_some()
+    }
+
+    // Set the scheduled phase aside, with the time it has run, for a one-off
+    // work phase of `minutes`; the schedule picks up again from there once it
+    // ends. Does nothing during deep work already.
+    pub fn start_deep_work(&mut self, minutes: u64) -> bool {
+        if self.deep_work.is_some() || self.stopwatch {
+            return false;
+        }
+        self.deep_work = Some(Suspended {
+            phase: self.phase,
+            elapsed: self.elapsed,
+            adjust: self.adjust,
+            paused_for: self.paused_for,
+        });
+        self.restart_pause_count();
+        self.phase = Phase::Work;
+        self.phase_started = clock::unix_now();
+        self.elapsed = 0;
+        self.adjust = (minutes * 60) as i64 - self.phase_secs(Phase::Work) as i64;
+        self.awaiting = false;
+        true
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        match self.paused_at.take() {
+            Some(at) => self.paused_for += at.elapsed(),
+            None => self.paused_at = Some(Instant::now()),
+        }
+    }
+
+    // how long the current phase has been paused, counting a pause under way
+    pub fn paused_secs(&self) -> u64 {
+        let current = self.paused_at.map_or(Duration::ZERO, |at| at.elapsed());
+        (self.paused_for + current).as_secs()
+    }
+
+    // start the pause count over for a new phase, which may begin paused
+    fn restart_pause_count(&mut self) {
+        self.paused_for = Duration::ZERO;
+        self.paused_at = self.paused_at.map(|_| Instant::now());
+    }
+
+    pub fn set_manual(&mut self, manual: bool) {
+        self.manual = manual;
+    }
+
+    pub fn set_stopwatch(&mut self, stopwatch: bool) {
+        self.stopwatch = stopwatch;
+    }
+
+    pub fn is_stopwatch(&self) -> bool {
+        self.stopwatch
+    }
+
+    // what the countdown shows: time left, or time so far for a stopwatch
+    pub fn clock_secs(&self) -> u64 {
+        if self.stopwatch {
+            self.elapsed
+        } else {
+            self.remaining_secs()
+        }
+    }
+
+    // true when a phase has ended in manual mode and the next one hasn't been started
+    pub fn is_awaiting(&self) -> bool {
+        self.awaiting
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.paused && !self.awaiting && !self.is_finished()
+    }
+
+    // hold the current phase until `start_next`, as manual mode does between phases
+    pub fn hold(&mut self) {
+        self.awaiting = true;
+    }
+
+    pub fn start_next(&mut self) {
+        if self.awaiting {
+            self.awaiting = false;
+            self.phase_started = clock::unix_now();
+        }
+    }
+
+    pub fn phase_secs(&self, phase: Phase) -> u64 {
+        let minutes = match phase {
+            Phase::Work => self.work,
+            Phase::ShortBreak => self.short_break,
+            Phase::LongBreak => self.long_break,
+        };
+        minutes * 60
+    }
+
+    // length of the phase under way before any '+' or '-': its plan step's,
+    // if there is a plan and deep work isn't standing in for it
+    pub fn planned_secs(&self) -> u64 {
+        match self.plan.get(self.step) {
+            Some(step) if self.deep_work.is_none() => step.minutes * 60,
+            _ => self.phase_secs(self.phase),
+        }
+    }
+
+    // planned length of whatever comes once the current phase ends
+    pub fn next_secs(&self) -> u64 {
+        if self.plan.is_empty() {
+            return self.phase_secs(self.next_phase());
+        }
+        // deep work gives way to the step it interrupted
+        let next = if self.deep_work.is_some() {
+            self.step
+        } else {
+            (self.step + 1) % self.plan.len()
+        };
+        self.plan[next].minutes * 60
+    }
+
+    // length of the phase under way, counting '+' and '-'
+    pub fn current_secs(&self) -> u64 {
+        (self.planned_secs() as i64 + self.adjust).max(0) as u64
+    }
+
+    pub fn remaining_secs(&self) -> u64 {
+        self.current_secs().saturating_sub(self.elapsed)
+    }
+
+    // a stopwatch fills up once per work length and starts over
+    pub fn progress(&self) -> f64 {
+        if self.stopwatch {
+            let work = self.phase_secs(Phase::Work).max(1);
+            return (self.elapsed % work) as f64 / work as f64;
+        }
+        (self.elapsed as f64 / self.current_secs() as f64).clamp(0.0, 1.0)
+    }
+
+    // Lengthen or shorten just this phase by whole minutes, never leaving
+    // less than a minute to go. Returns the new remaining time.
+    pub fn extend(&mut self, minutes: i64) -> u64 {
+        let target = self.remaining_secs() as i64 + minutes * 60;
+        let floor = (self.remaining_secs() as i64).min(60);
+        self.adjust += target.max(floor) - self.remaining_secs() as i64;
+        self.remaining_secs()
+    }
+
+    // Change the planned length of every phase like the current one, at
+    // least a minute; the phase under way keeps its length. Returns the new
+    // length in minutes.
+    pub fn adjust_plan(&mut self, minutes: i64) -> u64 {
+        let before = self.current_secs();
+        let length = match self.phase {
+            Phase::Work => &mut self.work,
+            Phase::ShortBreak => &mut self.short_break,
+            Phase::LongBreak => &mut self.long_break,
+        };
+        *length = (*length as i64 + minutes).max(1) as u64;
+        let mut length = *length;
+        if self.plan.is_empty() {
+            self.set_pomodoros_per_set(self.pomodoros_per_set);
+        } else {
+            // under a plan, every step of this kind moves by the same amount
+            for step in self.plan.iter_mut().filter(|step| step.phase == self.phase) {
+                step.minutes = (step.minutes as i64 + minutes).max(1) as u64;
+            }
+            self.set_secs = self.plan.iter().map(|step| step.minutes * 60).sum();
+            if self.deep_work.is_none() {
+                length = self.plan[self.step].minutes;
+            }
+        }
+        self.adjust = before as i64 - self.planned_secs() as i64;
+        length
+    }
+
+    // how far through the current set we are, counting finished phases in full
+    pub fn set_progress(&self) -> f64 {
+        let (phase, elapsed) = match &self.deep_work {
+            Some(suspended) => (suspended.phase, suspended.elapsed),
+            None => (self.phase, self.elapsed),
+        };
+        if !self.plan.is_empty() {
+            let before: u64 = self.plan[..self.step]
+                .iter()
+                .map(|step| step.minutes * 60)
+                .sum();
+            return ((before + elapsed) as f64 / self.set_secs as f64).clamp(0.0, 1.0);
+        }
+        let work = self.phase_secs(Phase::Work);
+        let short_break = self.phase_secs(Phase::ShortBreak);
+        let done = u64::from(self.completed % self.pomodoros_per_set);
+        let before = match phase {
+            Phase::Work => done * (work + short_break),
+            // done can be 0 here after switching to a smaller set size
+            Phase::ShortBreak => done * work + done.saturating_sub(1) * short_break,
+            Phase::LongBreak => self.set_secs - self.phase_secs(Phase::LongBreak),
+        };
+        ((before + elapsed) as f64 / self.set_secs as f64).clamp(0.0, 1.0)
+    }
+
+    // Advance the clock by one second, moving to the next phase when the
+    // current one runs out. Does nothing while paused, awaiting the next
+    // phase, or finished.
+    pub fn tick(&mut self) -> Option<Transition> {
+        if !self.is_running() {
+            return None;
+        }
+        self.elapsed += 1;
+        if self.elapsed >= self.current_secs() && !self.stopwatch {
+            return Some(self.advance());
+        }
+        None
+    }
+
+    // Run `ticks` ticks at once, e.g. after the machine was suspended. Phases
+    // that begin along the way are dated back as if each tick were a second.
+    pub fn fast_forward(&mut self, ticks: u64) -> Vec<Transition> {
+        let now = clock::unix_now();
+        let mut transitions = Vec::new();
+        for left in (0..ticks).rev() {
+            if !self.is_running() {
+                break;
+            }
+            if let Some(transition) = self.tick() {
+                self.phase_started = now.saturating_sub(left);
+                transitions.push(transition);
+            }
+        }
+        transitions
+    }
+
+    // Jump within the current phase; never crosses into another phase.
+    // Returns how many seconds the clock actually moved.
+    pub fn seek(&mut self, secs: i64) -> i64 {
+        let end = if self.stopwatch {
+            i64::MAX
+        } else {
+            self.current_secs() as i64
+        };
+        let target = (self.elapsed as i64 + secs).clamp(0, end);
+        let delta = target - self.elapsed as i64;
+        self.elapsed = target as u64;
+        delta
+    }
+
+    // end the current phase early, counting it as if it had run out
+    pub fn skip(&mut self) -> Transition {
+        self.advance()
+    }
+
+    // Pick up a phase cut short earlier as a fresh phase of just the time it
+    // had left.
+    pub fn resume(&mut self, phase: Phase, remaining_secs: u64) {
+        self.phase = phase;
+        self.deep_work = None;
+        // a plan carries on from its first step of that kind
+        if let Some(step) = self.plan.iter().position(|step| step.phase == phase) {
+            self.step = step;
+        }
+        self.restart_pause_count();
+        self.phase_started = clock::unix_now();
+        self.elapsed = 0;
+        self.adjust = remaining_secs as i64 - self.planned_secs() as i64;
+        self.awaiting = false;
+    }
+
+    pub fn reset(&mut self) {
+        self.step = 0;
+        self.phase = self.plan.first().map_or(Phase::Work, |step| step.phase);
+        self.phase_started = clock::unix_now();
+        self.elapsed = 0;
+        self.adjust = 0;
+        self.deep_work = None;
+        self.restart_pause_count();
+        self.completed = 0;
+        self.sets_finished = 0;
+        self.awaiting = false;
+    }
+
+    // what comes once the current phase ends; a Work phase counts itself as done
+    pub fn next_phase(&self) -> Phase {
+        if let Some(suspended) = &self.deep_work {
+            return suspended.phase;
+        }
+        if !self.plan.is_empty() {
+            return self.plan[(self.step + 1) % self.plan.len()].phase;
+        }
+        match self.phase {
+            Phase::Work => self.following_phase(self.completed + 1),
+            _ => self.following_phase(self.completed),
+        }
+    }
+
+    // the phase after the current one, given how many pomodoros are done so far
+    fn following_phase(&self, completed: u32) -> Phase {
+        match self.phase {
+            Phase::Work if completed.is_multiple_of(self.pomodoros_per_set) => Phase::LongBreak,
+            Phase::Work => Phase::ShortBreak,
+            Phase::ShortBreak | Phase::LongBreak => Phase::Work,
+        }
+    }
+
+    // the phase the schedule is on, even while deep work stands in for it
+    fn scheduled_phase(&self) -> Phase {
+        self.deep_work
+            .as_ref()
+            .map_or(self.phase, |suspended| suspended.phase)
+    }
+
+    fn advance(&mut self) -> Transition {
+        if let Some(suspended) = self.deep_work.take() {
+            let transition = Transition {
+                from: self.phase,
+                to: suspended.phase,
+                started: self.phase_started,
+                elapsed: self.elapsed,
+                planned: self.current_secs(),
+                paused_secs: self.paused_secs(),
+                deep_work: true,
+            };
+            self.phase = suspended.phase;
+            self.phase_started = clock::unix_now();
+            self.elapsed = suspended.elapsed;
+            self.adjust = suspended.adjust;
+            self.restart_pause_count();
+            self.paused_for = suspended.paused_for;
+            self.awaiting = self.manual;
+            return transition;
+        }
+        let planned = self.current_secs();
+        match self.phase {
+            Phase::Work => self.completed += 1,
+            Phase::LongBreak if self.plan.is_empty() => self.sets_finished += 1,
+            Phase::ShortBreak | Phase::LongBreak => {}
+        }
+        // a stopwatch was only ever going to run as long as it did
+        let (next, planned) = if self.stopwatch {
+            (Phase::Work, self.elapsed)
+        } else if !self.plan.is_empty() {
+            // a plan's set ends with its last step, whatever kind that is
+            self.step = (self.step + 1) % self.plan.len();
+            if self.step == 0 {
+                self.sets_finished += 1;
+            }
+            (self.plan[self.step].phase, planned)
+        } else {
+            (self.following_phase(self.completed), planned)
+        };
+        let transition = Transition {
+            from: self.phase,
+            to: next,
+            started: self.phase_started,
+            elapsed: self.elapsed,
+            planned,
+            paused_secs: self.paused_secs(),
+            deep_work: false,
+        };
+        self.restart_pause_count();
+        self.phase = next;
+        self.phase_started = clock::unix_now();
+        self.elapsed = 0;
+        self.adjust = 0;
+        self.awaiting = self.manual;
+        transition
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // one-minute work and short breaks, a two-minute long break, four per set
+    fn timer() -> Timer {
+        Timer::new(1, 1, 2, 4)
+    }
+
+    // tick until the phase under way ends
+    fn run_phase(timer: &mut Timer) -> Transition {
+        for _ in 0..24 * 3600 {
+            if let Some(transition) = timer.tick() {
+                return transition;
+            }
+        }
+        panic!("phase never ended");
+    }
+
+    #[test]
+    fn runs_work_then_break_then_work() {
+        let mut timer = timer();
+        assert_eq!(timer.current_phase(), Phase::Work);
+        assert_eq!(timer.remaining_secs(), 60);
+        for _ in 0..59 {
+            assert!(timer.tick().is_none());
+        }
+        assert_eq!(timer.remaining_secs(), 1);
+        let transition = timer.tick().unwrap();
+        assert_eq!(
+            (transition.from, transition.to),
+            (Phase::Work, Phase::ShortBreak)
+        );
+        assert_eq!(transition.elapsed, 60);
+        assert_eq!(timer.current_phase(), Phase::ShortBreak);
+        assert_eq!(timer.remaining_secs(), 60);
+        assert_eq!(timer.completed(), 1);
+        let transition = run_phase(&mut timer);
+        assert_eq!(
+            (transition.from, transition.to),
+            (Phase::ShortBreak, Phase::Work)
+        );
+        assert_eq!(timer.current_phase(), Phase::Work);
+        assert_eq!(timer.completed(), 1);
+    }
+
+    #[test]
+    fn counts_four_work_completions() {
+        let mut timer = timer();
+        while timer.completed() < 4 {
+            run_phase(&mut timer);
+        }
+        assert_eq!(timer.completed(), 4);
+        assert_eq!(timer.current_phase(), Phase::LongBreak);
+    }
+
+    #[test]
+    fn every_fourth_break_is_long() {
+        let mut timer = timer();
+        let mut breaks = Vec::new();
+        while timer.completed() < 8 {
+            let transition = run_phase(&mut timer);
+            if transition.from == Phase::Work {
+                breaks.push(transition.to);
+            }
+        }
+        use Phase::{LongBreak as L, ShortBreak as S};
+        assert_eq!(breaks, [S, S, S, L, S, S, S, L]);
+    }
+
+    #[test]
+    fn the_set_size_moves_the_long_break() {
+        let mut timer = Timer::new(1, 1, 2, 2);
+        let mut breaks = Vec::new();
+        while timer.completed() < 4 {
+            let transition = run_phase(&mut timer);
+            if transition.from == Phase::Work {
+                breaks.push(transition.to);
+            }
+        }
+        use Phase::{LongBreak as L, ShortBreak as S};
+        assert_eq!(breaks, [S, L, S, L]);
+    }
+
+    #[test]
+    fn skipping_work_counts_it_and_starts_a_full_break() {
+        let mut timer = timer();
+        for _ in 0..20 {
+            timer.tick();
+        }
+        let transition = timer.skip();
+        assert_eq!(
+            (transition.from, transition.to),
+            (Phase::Work, Phase::ShortBreak)
+        );
+        assert_eq!(transition.elapsed, 20);
+        assert_eq!(timer.completed(), 1);
+        assert_eq!(timer.elapsed(), 0);
+        assert_eq!(timer.remaining_secs(), 60);
+    }
+
+    #[test]
+    fn skipping_the_fourth_work_phase_goes_to_the_long_break() {
+        let mut timer = timer();
+        for _ in 0..3 {
+            timer.skip();
+            timer.skip();
+        }
+        assert_eq!(timer.skip().to, Phase::LongBreak);
+    }
+
+    #[test]
+    fn seeking_stops_at_the_start_of_the_phase() {
+        let mut timer = Timer::new(25, 5, 30, 4);
+        for _ in 0..30 {
+            timer.tick();
+        }
+        assert_eq!(timer.seek(-60), -30);
+        assert_eq!(timer.elapsed(), 0);
+        assert_eq!(timer.current_phase(), Phase::Work);
+    }
+
+    #[test]
+    fn seeking_stops_at_the_end_of_the_phase() {
+        let mut timer = Timer::new(25, 5, 30, 4);
+        assert_eq!(timer.seek(24 * 60 + 30), 24 * 60 + 30);
+        assert_eq!(timer.seek(60), 30);
+        assert_eq!(timer.remaining_secs(), 0);
+        assert_eq!(timer.current_phase(), Phase::Work);
+        assert_eq!(timer.completed(), 0);
+    }
+
+    #[test]
+    fn manual_mode_waits_at_the_boundary() {
+        let mut timer = timer();
+        timer.set_manual(true);
+        run_phase(&mut timer);
+        assert_eq!(timer.current_phase(), Phase::ShortBreak);
+        assert!(timer.is_awaiting());
+        for _ in 0..600 {
+            assert!(timer.tick().is_none());
+        }
+        assert_eq!(timer.elapsed(), 0);
+        timer.start_next();
+        assert!(!timer.is_awaiting());
+        assert_eq!(run_phase(&mut timer).to, Phase::Work);
+        assert!(timer.is_awaiting());
+    }
+
+    #[test]
+    fn ticking_does_nothing_once_every_set_is_done() {
+        let mut timer = timer();
+        timer.set_limit(1);
+        while !timer.is_finished() {
+            run_phase(&mut timer);
+        }
+        assert_eq!(timer.sets_finished(), 1);
+        assert_eq!(timer.completed(), 4);
+        let (phase, elapsed) = (timer.current_phase(), timer.elapsed());
+        for _ in 0..600 {
+            assert!(timer.tick().is_none());
+        }
+        assert_eq!(timer.current_phase(), phase);
+        assert_eq!(timer.elapsed(), elapsed);
+        assert!(!timer.is_running());
+    }
+
+    #[test]
+    fn the_next_phase_previews_the_long_break() {
+        let mut timer = timer();
+        let mut previews = Vec::new();
+        for _ in 0..8 {
+            previews.push(timer.next_phase());
+            assert_eq!(run_phase(&mut timer).to, *previews.last().unwrap());
+        }
+        use Phase::{LongBreak as L, ShortBreak as S, Work as W};
+        assert_eq!(previews, [S, W, S, W, S, W, L, W]);
+        assert_eq!(timer.next_secs(), 60);
+    }
+
+    #[test]
+    fn extending_changes_only_this_phase_and_leaves_a_minute() {
+        let mut timer = Timer::new(25, 5, 30, 4);
+        for _ in 0..60 {
+            timer.tick();
+        }
+        assert_eq!(timer.extend(5), 29 * 60);
+        assert_eq!(timer.extend(-40), 60);
+        assert_eq!(timer.remaining_secs(), 60);
+        run_phase(&mut timer);
+        run_phase(&mut timer);
+        // the next work phase is back to its usual length
+        assert_eq!(timer.remaining_secs(), 25 * 60);
+    }
+
+    #[test]
+    fn adjusting_the_plan_keeps_the_phase_under_way() {
+        let mut timer = Timer::new(25, 5, 30, 4);
+        for _ in 0..60 {
+            timer.tick();
+        }
+        assert_eq!(timer.adjust_plan(5), 30);
+        assert_eq!(timer.remaining_secs(), 24 * 60);
+        assert_eq!(timer.adjust_plan(-100), 1);
+        run_phase(&mut timer);
+        assert_eq!(timer.remaining_secs(), 5 * 60);
+        run_phase(&mut timer);
+        assert_eq!(timer.remaining_secs(), 60);
+    }
+
+    #[test]
+    fn counts_down_the_pomodoros_until_the_long_break() {
+        let mut timer = timer();
+        let mut counts = Vec::new();
+        while timer.completed() < 5 {
+            counts.push((timer.completed(), timer.until_long_break()));
+            run_phase(&mut timer);
+        }
+        assert_eq!(
+            counts,
+            [
+                (0, Some(4)),
+                (1, Some(3)),
+                (1, Some(3)),
+                (2, Some(2)),
+                (2, Some(2)),
+                (3, Some(1)),
+                (3, Some(1)),
+                // the long break itself, then a fresh set
+                (4, None),
+                (4, Some(4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn the_schedule_picks_up_where_deep_work_left_it() {
+        let mut timer = timer();
+        for _ in 0..2 {
+            run_phase(&mut timer);
+        }
+        // second work phase, part way through
+        for _ in 0..20 {
+            timer.tick();
+        }
+        assert!(timer.start_deep_work(2));
+        assert!(!timer.start_deep_work(2));
+        assert_eq!(timer.current_phase(), Phase::Work);
+        assert_eq!(timer.remaining_secs(), 120);
+        assert_eq!(timer.next_phase(), Phase::Work);
+        let transition = run_phase(&mut timer);
+        assert!(transition.deep_work);
+        assert_eq!(transition.elapsed, 120);
+        assert!(!timer.is_deep_work());
+        assert_eq!(timer.completed(), 1);
+        assert_eq!(timer.elapsed(), 20);
+        assert_eq!(timer.remaining_secs(), 40);
+        assert_eq!(run_phase(&mut timer).to, Phase::ShortBreak);
+        assert_eq!(timer.completed(), 2);
+    }
+
+    #[test]
+    fn deep_work_during_a_break_returns_to_that_break() {
+        let mut timer = timer();
+        for _ in 0..7 {
+            run_phase(&mut timer);
+        }
+        assert_eq!(timer.current_phase(), Phase::LongBreak);
+        timer.start_deep_work(1);
+        assert_eq!(run_phase(&mut timer).to, Phase::LongBreak);
+        assert_eq!(timer.completed(), 4);
+        run_phase(&mut timer);
+        assert_eq!(timer.current_phase(), Phase::Work);
+        assert_eq!(timer.until_long_break(), Some(4));
+    }
+
+    #[test]
+    fn a_stopwatch_counts_up_past_the_work_length() {
+        let mut timer = timer();
+        timer.set_stopwatch(true);
+        for second in 1..=150 {
+            assert!(timer.tick().is_none());
+            assert_eq!(timer.clock_secs(), second);
+        }
+        assert_eq!(timer.current_phase(), Phase::Work);
+        assert_eq!(timer.until_long_break(), None);
+        let transition = timer.skip();
+        assert_eq!((transition.elapsed, transition.planned), (150, 150));
+        assert_eq!(timer.current_phase(), Phase::Work);
+        assert_eq!(timer.clock_secs(), 0);
+    }
+
+    #[test]
+    fn paused_time_adds_up_across_pauses() {
+        let mut timer = timer();
+        // pause for `secs`, dating the pause back rather than sleeping
+        let pause = |timer: &mut Timer, secs| {
+            timer.toggle_pause();
+            timer.paused_at = Some(Instant::now() - Duration::from_secs(secs));
+            timer.toggle_pause();
+        };
+        for _ in 0..10 {
+            timer.tick();
+        }
+        pause(&mut timer, 30);
+        for _ in 0..10 {
+            timer.tick();
+        }
+        pause(&mut timer, 45);
+        assert_eq!(timer.paused_secs(), 75);
+        assert_eq!(timer.elapsed(), 20);
+        let transition = run_phase(&mut timer);
+        assert_eq!(transition.elapsed, 60);
+        assert_eq!(transition.paused_secs, 75);
+        // the break starts its own count
+        assert_eq!(timer.paused_secs(), 0);
+    }
+
+    #[test]
+    fn fast_forwarding_crosses_phase_boundaries() {
+        let mut timer = timer();
+        for _ in 0..30 {
+            timer.tick();
+        }
+        // a 100 second jump: the last 30 of work, a minute of break, 10 of work
+        let transitions = timer.fast_forward(100);
+        let phases: Vec<_> = transitions.iter().map(|t| (t.from, t.to)).collect();
+        assert_eq!(
+            phases,
+            [
+                (Phase::Work, Phase::ShortBreak),
+                (Phase::ShortBreak, Phase::Work)
+            ]
+        );
+        assert_eq!(transitions[0].elapsed, 60);
+        assert_eq!(timer.current_phase(), Phase::Work);
+        assert_eq!(timer.completed(), 1);
+        assert_eq!(timer.remaining_secs(), 50);
+        // the break is dated back to when it would have begun
+        let now = clock::unix_now();
+        assert!((10..=11).contains(&(now - timer.phase_started())));
+    }
+
+    #[test]
+    fn fast_forwarding_stops_while_paused() {
+        let mut timer = timer();
+        timer.toggle_pause();
+        assert!(timer.fast_forward(600).is_empty());
+        assert_eq!(timer.elapsed(), 0);
+    }
+
+    #[test]
+    fn switching_schedules_mid_cycle_takes_the_new_lengths() {
+        let mut timer = timer();
+        run_phase(&mut timer);
+        timer.fast_forward(20);
+        timer.set_schedule(&Schedule {
+            name: "long".to_string(),
+            work: 2,
+            short_break: 3,
+            long_break: 5,
+            pomodoros_per_set: 2,
+        });
+        assert_eq!(timer.current_phase(), Phase::ShortBreak);
+        assert_eq!(timer.remaining_secs(), 180);
+        assert_eq!(timer.completed(), 1);
+        assert_eq!(run_phase(&mut timer).to, Phase::Work);
+        assert_eq!(timer.remaining_secs(), 120);
+        assert_eq!(run_phase(&mut timer).to, Phase::LongBreak);
+        assert_eq!(timer.remaining_secs(), 300);
+        assert_eq!(timer.completed(), 2);
+    }
+}
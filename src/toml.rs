@@ -0,0 +1,264 @@
+// A small subset of TOML: tables, key/value pairs, strings, integers,
+// booleans and single-line arrays. Enough for the config file.
+
+use std::collections::BTreeMap;
+
+pub type Table = BTreeMap<String, Value>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+    Array(Vec<Value>),
+    Table(Table),
+}
+
+impl Value {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::String(_) => "string",
+            Value::Integer(_) => "integer",
+            Value::Boolean(_) => "boolean",
+            Value::Array(_) => "array",
+            Value::Table(_) => "table",
+        }
+    }
+}
+
+pub fn parse(input: &str) -> Result<Table, String> {
+    let mut root = Table::new();
+    let mut section: Vec<String> = Vec::new();
+    for (i, raw) in input.lines().enumerate() {
+        let line_no = i + 1;
+        let err = |msg: String| format!("line {}: {}", line_no, msg);
+        let line = strip_comment(raw).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[') {
+            let header = header
+                .strip_suffix(']')
+                .ok_or_else(|| err("unterminated table header".to_string()))?;
+            section = header.split('.').map(|k| k.trim().to_string()).collect();
+            if section.iter().any(|k| !is_bare_key(k)) {
+                return Err(err(format!("invalid table name '{}'", header)));
+            }
+            table_at(&mut root, &section).map_err(err)?;
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| err("expected `key = value`".to_string()))?;
+        let key = key.trim();
+        if !is_bare_key(key) {
+            return Err(err(format!("invalid key '{}'", key)));
+        }
+        let mut rest = value.trim();
+        let value = parse_value(&mut rest).map_err(err)?;
+        if !rest.trim().is_empty() {
+            return Err(err(format!(
+                "unexpected trailing characters '{}'",
+                rest.trim()
+            )));
+        }
+        let table = table_at(&mut root, &section).map_err(err)?;
+        if table.insert(key.to_string(), value).is_some() {
+            return Err(err(format!("duplicate key '{}'", key)));
+        }
+    }
+    Ok(root)
+}
+
+fn table_at<'a>(root: &'a mut Table, path: &[String]) -> Result<&'a mut Table, String> {
+    let mut table = root;
+    for key in path {
+        let entry = table
+            .entry(key.clone())
+            .or_insert_with(|| Value::Table(Table::new()));
+        table = match entry {
+            Value::Table(t) => t,
+            _ => return Err(format!("'{}' is not a table", key)),
+        };
+    }
+    Ok(table)
+}
+
+fn is_bare_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(q) => {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' && q == '"' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' => return &line[..i],
+            None => {}
+        }
+    }
+    line
+}
+
+fn parse_value(input: &mut &str) -> Result<Value, String> {
+    *input = input.trim_start();
+    match input.chars().next() {
+        Some('"') => parse_basic_string(input),
+        Some('\'') => {
+            let body = &input[1..];
+            let end = body
+                .find('\'')
+                .ok_or_else(|| "unterminated string".to_string())?;
+            let value = body[..end].to_string();
+            *input = &body[end + 1..];
+            Ok(Value::String(value))
+        }
+        Some('[') => {
+            *input = &input[1..];
+            let mut items = Vec::new();
+            loop {
+                *input = input.trim_start();
+                if let Some(rest) = input.strip_prefix(']') {
+                    *input = rest;
+                    return Ok(Value::Array(items));
+                }
+                items.push(parse_value(input)?);
+                *input = input.trim_start();
+                if let Some(rest) = input.strip_prefix(',') {
+                    *input = rest;
+                } else if !input.starts_with(']') {
+                    return Err("expected `,` or `]` in array".to_string());
+                }
+            }
+        }
+        Some(_) => {
+            let end = input
+                .find(|c: char| c == ',' || c == ']' || c.is_whitespace())
+                .unwrap_or(input.len());
+            let token = &input[..end];
+            *input = &input[end..];
+            match token {
+                "true" => Ok(Value::Boolean(true)),
+                "false" => Ok(Value::Boolean(false)),
+                _ => token
+                    .replace('_', "")
+                    .parse::<i64>()
+                    .map(Value::Integer)
+                    .map_err(|_| format!("invalid value '{}'", token)),
+            }
+        }
+        None => Err("missing value".to_string()),
+    }
+}
+
+fn parse_basic_string(input: &mut &str) -> Result<Value, String> {
+    let mut value = String::new();
+    let mut chars = input.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => {
+                *input = &input[i + 1..];
+                return Ok(Value::String(value));
+            }
+            '\\' => match chars.next().map(|(_, c)| c) {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some('"') => value.push('"'),
+                Some('\\') => value.push('\\'),
+                Some(other) => return Err(format!("unknown escape '\\{}'", other)),
+                None => break,
+            },
+            _ => value.push(c),
+        }
+    }
+    Err("unterminated string".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(s: &str) -> Value {
+        Value::String(s.to_string())
+    }
+
+    #[test]
+    fn reads_scalars_arrays_and_tables() {
+        let table = parse(
+            "# pomodoro config\n\
+             work = 1_500\n\
+             bell = false\n\
+             days = ['mon', \"tue\"]\n\
+             \n\
+             [theme.colors]\n\
+             work = \"red\" # trailing\n",
+        )
+        .unwrap();
+        assert_eq!(table["work"], Value::Integer(1500));
+        assert_eq!(table["bell"], Value::Boolean(false));
+        assert_eq!(
+            table["days"],
+            Value::Array(vec![string("mon"), string("tue")])
+        );
+        let Value::Table(theme) = &table["theme"] else {
+            panic!("theme is not a table");
+        };
+        let Value::Table(colors) = &theme["colors"] else {
+            panic!("colors is not a table");
+        };
+        assert_eq!(colors["work"], string("red"));
+    }
+
+    #[test]
+    fn unescapes_basic_strings_but_not_literal_ones() {
+        let table = parse(r#"a = "tab\there \"q\" \\ # not a comment\n""#).unwrap();
+        assert_eq!(table["a"], string("tab\there \"q\" \\ # not a comment\n"));
+        let table = parse(r"b = 'C:\temp\n'").unwrap();
+        assert_eq!(table["b"], string(r"C:\temp\n"));
+    }
+
+    #[test]
+    fn rejects_duplicate_keys() {
+        assert_eq!(
+            parse("work = 25\nwork = 50").unwrap_err(),
+            "line 2: duplicate key 'work'"
+        );
+        assert!(parse("[a]\nx = 1\n[a]\nx = 2").is_err());
+        assert!(parse("a = 1\n[a]").is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        for input in [
+            "[theme",
+            "[bad name]",
+            "work",
+            "work = ",
+            "work = 25 minutes",
+            "work = twenty",
+            "name = \"open",
+            "name = \"\\q\"",
+            "days = [1, 2",
+            "my key = 1",
+        ] {
+            assert!(parse(input).is_err(), "{:?} parsed", input);
+        }
+        assert_eq!(
+            parse("work = 25\nbreak = nope").unwrap_err(),
+            "line 2: invalid value 'nope'"
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use crate::history::SessionRecord;
+use std::{
+    io::{self, Write},
+    process::{Command, Stdio},
+    thread,
+};
+
+// Hand the POST to curl on its own thread so a slow or dead endpoint never
+// stalls the render loop. A failed attempt is logged and tried once more.
+pub fn post(url: &str, record: &SessionRecord) {
+    let url = url.to_string();
+    let body = record.to_json().to_string();
+    thread::spawn(move || {
+        for attempt in 1..=2 {
+            match send(&url, &body) {
+                Ok(()) => return,
+                Err(err) => log::warn!("webhook {} attempt {} failed: {}", url, attempt, err),
+            }
+        }
+    });
+}
+
+fn send(url: &str, body: &str) -> io::Result<()> {
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--max-time", "10"])
+        .args(["--header", "Content-Type: application/json"])
+        .args(["--data-binary", "@-", url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(body.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}